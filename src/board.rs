@@ -15,15 +15,27 @@ pub mod constants {
     pub const COL_RANGE: RangeInclusive<char> = 'a'..='h';
 }
 
+mod attacks;
+pub(crate) mod zobrist;
+
 use crate::board::constants::{BLANK_SQUARE, BOARD_SIZE, COLUMNS, LINES};
+use crate::errors::constants::INTERNAL_ERROR_04;
 use crate::movement::{Direction, Move, Position, PositionI8};
-use crate::pieces::Piece;
+use crate::pieces::types::Pawn;
+use crate::pieces::{Color, Piece, PieceType};
 
-type InternalBoard = [[Option<Piece>; BOARD_SIZE]; BOARD_SIZE];
+const SQUARES: usize = BOARD_SIZE * BOARD_SIZE;
+const PIECE_KINDS: usize = 6;
 
 #[derive(Copy, Clone, Debug, PartialEq)]
+/// Pieces on the board, both by square and as bitboards: one bit per square, where bit
+/// `line * 8 + col` stands for `Position { line, col }`
 pub struct Board {
-    board: InternalBoard,
+    squares: [Option<Piece>; SQUARES],
+    colors: [u64; 2],
+    kinds: [u64; PIECE_KINDS],
+    /// Zobrist key of the piece placement, updated as pieces are added and removed
+    key: u64,
 }
 
 impl Default for Board {
@@ -35,41 +47,151 @@ impl Default for Board {
 impl Board {
     pub fn new() -> Self {
         Self {
-            board: Default::default(),
+            squares: [None; SQUARES],
+            colors: [0; 2],
+            kinds: [0; PIECE_KINDS],
+            key: 0,
         }
     }
 
     pub fn get_piece(&self, position: Position) -> Option<Piece> {
-        self.board[position.line][position.col]
+        self.squares[square(position)]
     }
 
+    /// Mutable access to a piece, to update its castling and pawn flags. Its type and color must
+    /// not change, as the bitboards would go out of sync.
     pub fn get_piece_as_mut(&mut self, position: Position) -> Option<&mut Piece> {
-        self.board[position.line][position.col].as_mut()
+        self.squares[square(position)].as_mut()
     }
 
     pub fn update_piece(&mut self, position: Position, piece: Piece) {
-        self.board[position.line][position.col] = Some(piece);
+        self.add_piece(piece, position);
     }
 
     pub fn add_piece(&mut self, piece: Piece, pos: Position) {
-        self.board[pos.line][pos.col] = Some(piece);
+        self.remove_piece(pos);
+
+        let square = square(pos);
+        let (color, kind) = (color_index(piece.color), kind_index(&piece.piece_type));
+        self.colors[color] |= 1 << square;
+        self.kinds[kind] |= 1 << square;
+        self.key ^= zobrist::piece_key(color, kind, square);
+        self.squares[square] = Some(piece);
+    }
+
+    pub fn remove_piece(&mut self, position: Position) -> Option<Piece> {
+        let square = square(position);
+        let piece = self.squares[square].take()?;
+
+        let (color, kind) = (color_index(piece.color), kind_index(&piece.piece_type));
+        self.colors[color] &= !(1 << square);
+        self.kinds[kind] &= !(1 << square);
+        self.key ^= zobrist::piece_key(color, kind, square);
+
+        Some(piece)
     }
 
     pub fn is_position_occupied(&self, position: Position) -> bool {
-        self.board[position.line][position.col].is_some()
+        self.occupancy() & (1 << square(position)) != 0
     }
 
     pub fn move_piece(&mut self, origin: Position, destination: Position) {
-        self.board[destination.line][destination.col] = self.get_piece(origin);
+        match self.remove_piece(origin) {
+            Some(piece) => self.add_piece(piece, destination),
+            None => {
+                self.remove_piece(destination);
+            }
+        }
+    }
+
+    /// Zobrist key of the piece placement, kept up to date as pieces move
+    pub fn key(&self) -> u64 {
+        self.key
+    }
 
-        self.board[origin.line][origin.col] = None;
+    /// Zobrist key of the piece placement computed from every piece, which [`Board::key`] always
+    /// matches
+    pub fn compute_key(&self) -> u64 {
+        self.into_iter().fold(0, |key, (piece, position)| {
+            key ^ zobrist::piece_key(
+                color_index(piece.color),
+                kind_index(&piece.piece_type),
+                square(position),
+            )
+        })
+    }
+
+    /// Bitboard of every occupied square
+    pub fn occupancy(&self) -> u64 {
+        self.colors[0] | self.colors[1]
+    }
+
+    /// Bitboard of the squares occupied by the given color
+    pub fn pieces(&self, color: Color) -> u64 {
+        self.colors[color_index(color)]
+    }
+
+    /// Bitboard of the squares occupied by pieces of the given type and color
+    pub fn pieces_of_type(&self, piece_type: &PieceType, color: Color) -> u64 {
+        self.kinds[kind_index(piece_type)] & self.colors[color_index(color)]
+    }
+
+    /// Positions of the squares set in a bitboard, in the order the board iterator visits them
+    pub fn positions(bitboard: u64) -> impl Iterator<Item = Position> {
+        let mut remaining = bitboard;
+
+        std::iter::from_fn(move || {
+            if remaining == 0 {
+                return None;
+            }
+
+            let square = remaining.trailing_zeros() as usize;
+            remaining &= remaining - 1;
+
+            Some(position(square))
+        })
+    }
+
+    /// Bitboard of the squares the piece standing on `position` attacks, whether they are empty
+    /// or occupied by any color. Pawns attack diagonally only.
+    pub fn attacks_from(&self, piece: &Piece, position: Position) -> u64 {
+        let square = square(position);
+        let occupancy = self.occupancy();
+
+        match piece.piece_type {
+            PieceType::Bishop => attacks::bishop_attacks(square, occupancy),
+            PieceType::King(_) => attacks::king_attacks(square),
+            PieceType::Knight => attacks::knight_attacks(square),
+            PieceType::Pawn(_) => attacks::pawn_attacks(piece.color, square),
+            PieceType::Queen => attacks::queen_attacks(square, occupancy),
+            PieceType::Rook(_) => attacks::rook_attacks(square, occupancy),
+            PieceType::None => panic!("{}", INTERNAL_ERROR_04),
+        }
+    }
+
+    /// Whether any piece of the given color attacks the square, looking from the square outwards
+    /// with each piece type's attack pattern
+    pub fn is_square_attacked(&self, position: Position, by: Color) -> bool {
+        let square = square(position);
+        let occupancy = self.occupancy();
+        let kind = |piece_type: &PieceType| self.pieces_of_type(piece_type, by);
+
+        let queens = kind(&PieceType::Queen);
+        let diagonal_sliders = kind(&PieceType::Bishop) | queens;
+        let straight_sliders = kind(&PieceType::Rook(Default::default())) | queens;
+
+        attacks::pawn_attacks(by.opposite(), square) & kind(&PieceType::Pawn(Pawn::new())) != 0
+            || attacks::knight_attacks(square) & kind(&PieceType::Knight) != 0
+            || attacks::king_attacks(square) & kind(&PieceType::King(Default::default())) != 0
+            || attacks::bishop_attacks(square, occupancy) & diagonal_sliders != 0
+            || attacks::rook_attacks(square, occupancy) & straight_sliders != 0
     }
 
     pub fn print_board(&self) {
         for (line, line_chess) in (0..BOARD_SIZE).zip(LINES.iter()) {
             print!("{line_chess} ");
             for column in 0..BOARD_SIZE {
-                let maybe_piece = self.board[line][column];
+                let maybe_piece = self.get_piece(Position::new(line, column));
                 match maybe_piece {
                     Some(piece) => print!("{piece} "),
                     None => print!("{BLANK_SQUARE} "),
@@ -110,6 +232,10 @@ impl Board {
     }
 
     pub fn perform_move(&mut self, move_: Move) {
+        if let Some(captured) = move_.en_passant {
+            self.remove_piece(captured);
+        }
+
         if move_.source() != move_.destination() {
             self.move_piece(move_.source(), move_.destination());
         }
@@ -119,9 +245,27 @@ impl Board {
         {
             self.move_piece(additional.source, additional.destination);
         }
+
+        if let Some(promotion) = move_.promotion
+            && let Some(pawn) = self.get_piece(move_.destination())
+        {
+            let mut promoted = Piece::new(promotion, pawn.color);
+            promoted.deny_castling_rights();
+
+            self.update_piece(move_.destination(), promoted);
+        }
     }
 
     pub fn undo_move(&mut self, move_: Move, captured_piece: Option<Piece>) {
+        if move_.is_promotion()
+            && let Some(promoted) = self.get_piece(move_.destination())
+        {
+            let mut pawn = Piece::new(PieceType::Pawn(Pawn::new()), promoted.color);
+            pawn.deny_two_rows();
+
+            self.update_piece(move_.destination(), pawn);
+        }
+
         if move_.source() != move_.destination() {
             self.move_piece(move_.destination(), move_.source());
         }
@@ -133,14 +277,15 @@ impl Board {
         }
 
         if let Some(piece) = captured_piece {
-            self.add_piece(piece, move_.destination())
+            self.add_piece(piece, move_.capture_position())
         }
     }
 }
 
 pub struct BoardPieceIterator<'a> {
     board: &'a Board,
-    curr_pos: Position,
+    /// Occupied squares not visited yet
+    remaining: u64,
 }
 
 impl<'a> IntoIterator for &'a Board {
@@ -150,7 +295,7 @@ impl<'a> IntoIterator for &'a Board {
     fn into_iter(self) -> Self::IntoIter {
         BoardPieceIterator {
             board: self,
-            curr_pos: Default::default(),
+            remaining: self.occupancy(),
         }
     }
 }
@@ -159,15 +304,44 @@ impl Iterator for BoardPieceIterator<'_> {
     type Item = (Piece, Position);
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut next = self.curr_pos.next();
-        while let Some(pos) = next {
-            if let Some(piece) = self.board.get_piece(pos) {
-                return Some((piece, pos));
-            }
-            next = self.curr_pos.next();
+        if self.remaining == 0 {
+            return None;
         }
 
-        None
+        let square = self.remaining.trailing_zeros() as usize;
+        self.remaining &= self.remaining - 1;
+
+        let piece = self.board.squares[square]?;
+
+        Some((piece, position(square)))
+    }
+}
+
+/// Index of the bit standing for the position in a bitboard
+pub(crate) fn square(position: Position) -> usize {
+    position.line * BOARD_SIZE + position.col
+}
+
+fn position(square: usize) -> Position {
+    Position::new(square / BOARD_SIZE, square % BOARD_SIZE)
+}
+
+fn color_index(color: Color) -> usize {
+    match color {
+        Color::White => 0,
+        Color::Black => 1,
+    }
+}
+
+fn kind_index(piece_type: &PieceType) -> usize {
+    match piece_type {
+        PieceType::Pawn(_) => 0,
+        PieceType::Knight => 1,
+        PieceType::Bishop => 2,
+        PieceType::Rook(_) => 3,
+        PieceType::Queen => 4,
+        PieceType::King(_) => 5,
+        PieceType::None => panic!("{}", INTERNAL_ERROR_04),
     }
 }
 
@@ -210,8 +384,94 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Duplicate piece position in initial setup")]
-    fn test_setup_board_panics_on_duplicate_square() {
-        let _ = setup_board(Some("tests/duplicate_square.txt"));
+    fn test_iterator_visits_every_piece() {
+        let board = setup_board(None);
+
+        let pieces: Vec<(Piece, Position)> = board.into_iter().collect();
+
+        assert_eq!(pieces.len(), 32);
+        assert_eq!(pieces.first().unwrap().1, Position::new(0, 0));
+        assert_eq!(pieces.last().unwrap().1, Position::new(7, 7));
+    }
+
+    #[test]
+    fn test_promotion_perform_and_undo() {
+        let mut board = setup_board(Some("tests/promotion.txt"));
+        board.update_piece_state(Position::new(1, 4));
+        let initial_board_snapshot = board;
+
+        let mut promotion = Move::new(Position::new(1, 4), Position::new(0, 4));
+        promotion.promotion = Some(PieceType::Rook(Default::default()));
+
+        board.perform_move(promotion);
+
+        let promoted = board.get_piece(Position::new(0, 4)).unwrap();
+        assert!(matches!(promoted.piece_type, PieceType::Rook(_)));
+        assert!(!promoted.is_short_castling_available());
+        assert!(!promoted.is_long_castling_available());
+        assert!(board.get_piece(Position::new(1, 4)).is_none());
+
+        board.undo_move(promotion, None);
+
+        assert_eq!(board, initial_board_snapshot);
+    }
+
+    #[test]
+    fn test_overwriting_and_removing_keep_bitboards_in_sync() {
+        let mut board = setup_board(None);
+        let e2 = Position::new(6, 4);
+
+        board.add_piece(Piece::new(PieceType::Knight, Color::Black), e2);
+
+        assert_eq!(board.pieces(Color::White).count_ones(), 15);
+        assert_eq!(board.pieces(Color::Black).count_ones(), 17);
+
+        board.move_piece(e2, Position::new(4, 4));
+
+        assert_eq!(board.remove_piece(e2), None);
+        assert_eq!(board.occupancy().count_ones(), 32);
+        assert_eq!(
+            Board::positions(board.pieces(Color::Black)).last(),
+            Some(Position::new(4, 4))
+        );
+    }
+
+    #[test]
+    fn test_square_attacks() {
+        let board = setup_board(None);
+
+        assert!(board.is_square_attacked(Position::new(5, 0), Color::White));
+        assert!(board.is_square_attacked(Position::new(5, 7), Color::White));
+        assert!(!board.is_square_attacked(Position::new(4, 4), Color::White));
+        assert!(board.is_square_attacked(Position::new(2, 2), Color::Black));
+        assert!(!board.is_square_attacked(Position::new(5, 2), Color::Black));
+
+        let knight = board.get_piece(Position::new(7, 6)).unwrap();
+        assert_eq!(
+            Board::positions(board.attacks_from(&knight, Position::new(7, 6))).collect::<Vec<_>>(),
+            vec![
+                Position::new(5, 5),
+                Position::new(5, 7),
+                Position::new(6, 4)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sliders_are_blocked() {
+        let mut board = Board::new();
+        board.add_piece(
+            Piece::new(PieceType::Queen, Color::White),
+            Position::new(7, 3),
+        );
+        board.add_piece(
+            Piece::new(PieceType::Pawn(Pawn::new()), Color::White),
+            Position::new(4, 3),
+        );
+
+        assert!(board.is_square_attacked(Position::new(5, 3), Color::White));
+        assert!(!board.is_square_attacked(Position::new(3, 3), Color::White));
+        assert!(board.is_square_attacked(Position::new(3, 7), Color::White));
+        assert!(board.is_square_attacked(Position::new(3, 2), Color::White));
     }
 }
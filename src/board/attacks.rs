@@ -0,0 +1,312 @@
+//! Attack sets as bitboards, where bit `line * 8 + col` stands for the square at
+//! `Position { line, col }` (bit 0 is *a8* and bit 63 is *h1*). Knight, King and pawn attacks are
+//! precomputed at compile time, while Bishop and Rook attacks are looked up in tables indexed by
+//! the blockers extracted with PEXT, in hardware when the CPU supports BMI2.
+
+use crate::board::constants::BOARD_SIZE;
+use crate::pieces::Color;
+use std::sync::LazyLock;
+
+const SQUARES: usize = BOARD_SIZE * BOARD_SIZE;
+
+const KNIGHT_OFFSETS: [(i8, i8); 8] = [
+    (-2, -1),
+    (-2, 1),
+    (-1, -2),
+    (-1, 2),
+    (1, -2),
+    (1, 2),
+    (2, -1),
+    (2, 1),
+];
+const KING_OFFSETS: [(i8, i8); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+const ROOK_DIRECTIONS: [(i8, i8); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+const BISHOP_DIRECTIONS: [(i8, i8); 4] = [(-1, -1), (-1, 1), (1, -1), (1, 1)];
+
+const KNIGHT_ATTACKS: [u64; SQUARES] = leaper_attacks(&KNIGHT_OFFSETS);
+const KING_ATTACKS: [u64; SQUARES] = leaper_attacks(&KING_OFFSETS);
+/// White pawns capture towards line 0, Black pawns towards line 7
+const PAWN_ATTACKS: [[u64; SQUARES]; 2] = [
+    leaper_attacks(&[(-1, -1), (-1, 1)]),
+    leaper_attacks(&[(1, -1), (1, 1)]),
+];
+
+static ROOK_TABLE: LazyLock<SlidingTable> = LazyLock::new(|| SlidingTable::new(&ROOK_DIRECTIONS));
+static BISHOP_TABLE: LazyLock<SlidingTable> =
+    LazyLock::new(|| SlidingTable::new(&BISHOP_DIRECTIONS));
+
+pub fn knight_attacks(square: usize) -> u64 {
+    KNIGHT_ATTACKS[square]
+}
+
+pub fn king_attacks(square: usize) -> u64 {
+    KING_ATTACKS[square]
+}
+
+/// Squares a pawn of the given color standing on `square` captures on
+pub fn pawn_attacks(color: Color, square: usize) -> u64 {
+    match color {
+        Color::White => PAWN_ATTACKS[0][square],
+        Color::Black => PAWN_ATTACKS[1][square],
+    }
+}
+
+pub fn rook_attacks(square: usize, occupancy: u64) -> u64 {
+    ROOK_TABLE.attacks(square, occupancy)
+}
+
+pub fn bishop_attacks(square: usize, occupancy: u64) -> u64 {
+    BISHOP_TABLE.attacks(square, occupancy)
+}
+
+pub fn queen_attacks(square: usize, occupancy: u64) -> u64 {
+    rook_attacks(square, occupancy) | bishop_attacks(square, occupancy)
+}
+
+/// Attacks of a sliding piece for every subset of the squares that can block it
+struct SlidingTable {
+    /// Squares whose occupancy matters, excluding the board edges the rays end on
+    masks: [u64; SQUARES],
+    /// Start of the attacks of each square in `attacks`
+    offsets: [usize; SQUARES],
+    attacks: Vec<u64>,
+    /// Whether the CPU has the PEXT instruction, detected once rather than on every lookup
+    #[cfg(target_arch = "x86_64")]
+    has_bmi2: bool,
+}
+
+impl SlidingTable {
+    fn new(directions: &[(i8, i8)]) -> Self {
+        let mut masks = [0; SQUARES];
+        let mut offsets = [0; SQUARES];
+        let mut attacks = Vec::new();
+
+        for square in 0..SQUARES {
+            let mask = blocker_mask(square, directions);
+            masks[square] = mask;
+            offsets[square] = attacks.len();
+            attacks.resize(attacks.len() + (1 << mask.count_ones()), 0);
+
+            // Visit every subset of the mask, which PEXT maps to consecutive indexes
+            let mut blockers: u64 = 0;
+            loop {
+                attacks[offsets[square] + pext(blockers, mask) as usize] =
+                    ray_attacks(square, blockers, directions);
+
+                blockers = blockers.wrapping_sub(mask) & mask;
+                if blockers == 0 {
+                    break;
+                }
+            }
+        }
+
+        Self {
+            masks,
+            offsets,
+            attacks,
+            #[cfg(target_arch = "x86_64")]
+            has_bmi2: is_x86_feature_detected!("bmi2"),
+        }
+    }
+
+    fn attacks(&self, square: usize, occupancy: u64) -> u64 {
+        self.attacks[self.offsets[square] + self.index(occupancy, self.masks[square]) as usize]
+    }
+
+    /// Index of the attacks for the blockers in `occupancy` among the squares of `mask`
+    fn index(&self, occupancy: u64, mask: u64) -> u64 {
+        #[cfg(target_arch = "x86_64")]
+        if self.has_bmi2 {
+            // SAFETY: the CPU was checked for BMI2 support when building the table
+            return unsafe { hardware_pext(occupancy, mask) };
+        }
+
+        pext(occupancy, mask)
+    }
+}
+
+/// Gather the bits of `value` selected by `mask` into the low bits of the result, with the PEXT
+/// instruction
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "bmi2")]
+fn hardware_pext(value: u64, mask: u64) -> u64 {
+    std::arch::x86_64::_pext_u64(value, mask)
+}
+
+/// Gather the bits of `value` selected by `mask` into the low bits of the result, one bit at a
+/// time for CPUs without BMI2
+fn pext(value: u64, mask: u64) -> u64 {
+    let mut result = 0;
+    let mut remaining_mask = mask;
+    let mut bit = 1;
+
+    while remaining_mask != 0 {
+        let lowest = remaining_mask & remaining_mask.wrapping_neg();
+        if value & lowest != 0 {
+            result |= bit;
+        }
+        remaining_mask &= remaining_mask - 1;
+        bit <<= 1;
+    }
+
+    result
+}
+
+const fn offset_square(square: usize, line_offset: i8, col_offset: i8) -> Option<usize> {
+    let line = (square / BOARD_SIZE) as i8 + line_offset;
+    let col = (square % BOARD_SIZE) as i8 + col_offset;
+
+    if line >= 0 && line < BOARD_SIZE as i8 && col >= 0 && col < BOARD_SIZE as i8 {
+        Some(line as usize * BOARD_SIZE + col as usize)
+    }
+    else {
+        None
+    }
+}
+
+const fn leaper_attacks(offsets: &[(i8, i8)]) -> [u64; SQUARES] {
+    let mut attacks = [0; SQUARES];
+
+    let mut square = 0;
+    while square < SQUARES {
+        let mut index = 0;
+        while index < offsets.len() {
+            if let Some(target) = offset_square(square, offsets[index].0, offsets[index].1) {
+                attacks[square] |= 1 << target;
+            }
+            index += 1;
+        }
+        square += 1;
+    }
+
+    attacks
+}
+
+/// Walk each ray from the square, stopping on the first occupied square, which is attacked
+fn ray_attacks(square: usize, occupancy: u64, directions: &[(i8, i8)]) -> u64 {
+    let mut attacks = 0;
+
+    for &(line_step, col_step) in directions {
+        let mut current = square;
+        while let Some(next) = offset_square(current, line_step, col_step) {
+            attacks |= 1 << next;
+            if occupancy & (1 << next) != 0 {
+                break;
+            }
+            current = next;
+        }
+    }
+
+    attacks
+}
+
+/// Squares along each ray that could block it, the last square of the ray never blocking anything
+fn blocker_mask(square: usize, directions: &[(i8, i8)]) -> u64 {
+    let mut mask = 0;
+
+    for &(line_step, col_step) in directions {
+        let mut current = square;
+        while let Some(next) = offset_square(current, line_step, col_step) {
+            if offset_square(next, line_step, col_step).is_none() {
+                break;
+            }
+            mask |= 1 << next;
+            current = next;
+        }
+    }
+
+    mask
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_helper::Xorshift;
+
+    const A8: usize = 0;
+    const D4: usize = 35;
+    const H1: usize = 63;
+
+    #[test]
+    fn test_leaper_attacks() {
+        assert_eq!(knight_attacks(A8).count_ones(), 2);
+        assert_eq!(knight_attacks(D4).count_ones(), 8);
+        assert_eq!(king_attacks(H1).count_ones(), 3);
+        assert_eq!(king_attacks(D4).count_ones(), 8);
+    }
+
+    #[test]
+    fn test_pawn_attacks() {
+        // A White pawn on d4 captures on c5 and e5, a Black one on c3 and e3
+        assert_eq!(pawn_attacks(Color::White, D4), (1 << 26) | (1 << 28));
+        assert_eq!(pawn_attacks(Color::Black, D4), (1 << 42) | (1 << 44));
+        assert_eq!(pawn_attacks(Color::White, A8), 0);
+    }
+
+    #[test]
+    fn test_sliding_attacks_on_empty_board() {
+        assert_eq!(rook_attacks(D4, 0).count_ones(), 14);
+        assert_eq!(bishop_attacks(D4, 0).count_ones(), 13);
+        assert_eq!(bishop_attacks(A8, 0).count_ones(), 7);
+        assert_eq!(queen_attacks(H1, 0).count_ones(), 21);
+    }
+
+    #[test]
+    fn test_sliding_attacks_stop_on_blockers() {
+        // Blockers on d6 and f4: the Rook attacks them but not the squares behind
+        let occupancy = (1 << 19) | (1 << 37);
+        let attacks = rook_attacks(D4, occupancy);
+
+        assert_ne!(attacks & (1 << 19), 0);
+        assert_eq!(attacks & (1 << 11), 0);
+        assert_ne!(attacks & (1 << 37), 0);
+        assert_eq!(attacks & (1 << 38), 0);
+        assert_eq!(attacks.count_ones(), 10);
+    }
+
+    #[test]
+    #[cfg(target_arch = "x86_64")]
+    fn test_hardware_pext_matches_software() {
+        if !is_x86_feature_detected!("bmi2") {
+            return;
+        }
+        let mut rng = Xorshift(0x2545_f491_4f6c_dd1d);
+
+        for _ in 0..1024 {
+            let value = rng.next(usize::MAX) as u64;
+            let mask = rng.next(usize::MAX) as u64;
+
+            // SAFETY: BMI2 support was just checked
+            assert_eq!(unsafe { hardware_pext(value, mask) }, pext(value, mask));
+        }
+    }
+
+    #[test]
+    fn test_lookup_matches_ray_walk() {
+        let mut rng = Xorshift(0x853c_49e6_748f_ea9b);
+
+        for square in 0..SQUARES {
+            for _ in 0..64 {
+                let occupancy = (rng.next(usize::MAX) as u64) & (rng.next(usize::MAX) as u64);
+
+                assert_eq!(
+                    rook_attacks(square, occupancy),
+                    ray_attacks(square, occupancy, &ROOK_DIRECTIONS)
+                );
+                assert_eq!(
+                    bishop_attacks(square, occupancy),
+                    ray_attacks(square, occupancy, &BISHOP_DIRECTIONS)
+                );
+            }
+        }
+    }
+}
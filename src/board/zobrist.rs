@@ -0,0 +1,49 @@
+//! Random keys XORed together into a 64-bit position key, generated at compile time from a fixed
+//! seed so that keys are identical across runs
+
+use crate::board::constants::BOARD_SIZE;
+
+const SQUARES: usize = BOARD_SIZE * BOARD_SIZE;
+const PIECE_KEYS: usize = 2 * 6 * SQUARES;
+const SEED: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Keys for each color, piece kind and square, then the side to move, the four castling rights
+/// and the eight en passant files
+const KEYS: [u64; PIECE_KEYS + 1 + 4 + BOARD_SIZE] = generate_keys();
+
+/// Key of a piece, given its color and kind indexes and its square
+pub fn piece_key(color: usize, kind: usize, square: usize) -> u64 {
+    KEYS[(color * 6 + kind) * SQUARES + square]
+}
+
+/// Key toggled when Black is to move
+pub fn black_to_move_key() -> u64 {
+    KEYS[PIECE_KEYS]
+}
+
+/// Key of a castling right, indexed by color (White first) then side (short first)
+pub fn castling_key(right: usize) -> u64 {
+    KEYS[PIECE_KEYS + 1 + right]
+}
+
+pub fn en_passant_key(col: usize) -> u64 {
+    KEYS[PIECE_KEYS + 5 + col]
+}
+
+const fn generate_keys<const N: usize>() -> [u64; N] {
+    let mut keys = [0; N];
+    let mut state = SEED;
+
+    let mut index = 0;
+    while index < N {
+        // splitmix64, whose outputs are all distinct
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut key = state;
+        key = (key ^ (key >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        key = (key ^ (key >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        keys[index] = key ^ (key >> 31);
+        index += 1;
+    }
+
+    keys
+}
@@ -0,0 +1,605 @@
+//! Move search for the side to move: a negamax search with alpha-beta pruning, deepened one ply
+//! at a time until a depth, node or time limit is reached, with a quiescence search over captures
+//! at the leaves.
+
+mod transposition_table;
+
+pub use transposition_table::{Bound, DEFAULT_SIZE_MB, Entry, TranspositionTable};
+
+use crate::GameState;
+use crate::eval::{evaluate, piece_value};
+use crate::movement::Move;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Score of a checkmate delivered at the root, decreased by one for each ply until it happens
+pub const MATE_SCORE: i32 = 30_000;
+/// Greatest depth iterative deepening can reach
+pub const MAX_DEPTH: usize = 64;
+
+const INFINITY: i32 = MATE_SCORE + 1;
+/// Scores above this bound, or below its opposite, are mate scores
+const MATE_BOUND: i32 = MATE_SCORE - MAX_PLY as i32;
+/// Deepest ply searched, quiescence included
+const MAX_PLY: usize = 128;
+/// Nodes searched between two checks of the time limit
+const TIME_CHECK_INTERVAL: u64 = 1024;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+/// Conditions ending a search: it stops as soon as any of the given limits is reached, and at
+/// [`MAX_DEPTH`] when none is given
+pub struct SearchLimits {
+    pub depth: Option<usize>,
+    pub nodes: Option<u64>,
+    pub time: Option<Duration>,
+}
+
+impl SearchLimits {
+    pub fn depth(depth: usize) -> Self {
+        Self {
+            depth: Some(depth),
+            ..Default::default()
+        }
+    }
+
+    pub fn nodes(nodes: u64) -> Self {
+        Self {
+            nodes: Some(nodes),
+            ..Default::default()
+        }
+    }
+
+    pub fn time(time: Duration) -> Self {
+        Self {
+            time: Some(time),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+/// Outcome of the deepest iteration the search completed
+pub struct SearchResult {
+    /// Best move found, or `None` when the side to move has no legal move
+    pub best_move: Option<Move>,
+    /// Score in centipawns from the point of view of the side to move, mates excepted (see
+    /// [`SearchResult::mate_in`])
+    pub score: i32,
+    pub depth: usize,
+    pub nodes: u64,
+    /// Moves expected from both sides, starting with the best move
+    pub principal_variation: Vec<Move>,
+}
+
+impl SearchResult {
+    /// Number of moves (not plies) until a checkmate: positive when the side to move delivers
+    /// it, negative when it receives it, and `None` when no mate was found
+    pub fn mate_in(&self) -> Option<i32> {
+        if self.score > MATE_BOUND {
+            Some((MATE_SCORE - self.score + 1) / 2)
+        }
+        else if self.score < -MATE_BOUND {
+            Some(-(MATE_SCORE + self.score) / 2)
+        }
+        else {
+            None
+        }
+    }
+}
+
+#[derive(Debug)]
+/// Searches positions for the best move, within [`SearchLimits`]
+pub struct Engine {
+    limits: SearchLimits,
+    start: Instant,
+    nodes: u64,
+    stopped: bool,
+    /// Principal variation of the previous iteration, searched first
+    previous_variation: Vec<Move>,
+    /// Whether the current node lies on the previous principal variation
+    on_previous_variation: bool,
+    transposition_table: TranspositionTable,
+    /// Set from another thread to stop the search
+    stop_signal: Arc<AtomicBool>,
+}
+
+impl Default for Engine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Engine {
+    pub fn new() -> Self {
+        Self::with_hash_size(DEFAULT_SIZE_MB)
+    }
+
+    /// Create an engine whose transposition table takes up at most `size_mb` megabytes, or none
+    /// at all with a size of 0
+    pub fn with_hash_size(size_mb: usize) -> Self {
+        Self {
+            limits: SearchLimits::default(),
+            start: Instant::now(),
+            nodes: 0,
+            stopped: false,
+            previous_variation: Vec::new(),
+            on_previous_variation: false,
+            transposition_table: TranspositionTable::new(size_mb),
+            stop_signal: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Flag stopping the current search as soon as it is set, typically from another thread. The
+    /// flag is not cleared by the engine: a search started while it is set stops right away.
+    pub fn stop_signal(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.stop_signal)
+    }
+
+    /// Forget the positions searched so far, so that a new game does not depend on the previous
+    /// ones
+    pub fn new_game(&mut self) {
+        self.transposition_table.clear();
+    }
+
+    /// Search the position deeper and deeper until a limit is reached, and return the result of
+    /// the last iteration completed. A partial iteration is only used when not even the first
+    /// one could complete.
+    pub fn search(&mut self, game_state: &GameState, limits: SearchLimits) -> SearchResult {
+        self.search_with_progress(game_state, limits, |_| {})
+    }
+
+    /// Same as [`Engine::search`], calling `progress` with the result of each iteration as soon as
+    /// it completes
+    pub fn search_with_progress(
+        &mut self,
+        game_state: &GameState,
+        limits: SearchLimits,
+        mut progress: impl FnMut(&SearchResult),
+    ) -> SearchResult {
+        self.limits = limits;
+        self.start = Instant::now();
+        self.nodes = 0;
+        self.stopped = false;
+        self.transposition_table.new_search();
+
+        let mut game_state = game_state.clone();
+        let mut result = SearchResult::default();
+        let max_depth = limits.depth.unwrap_or(MAX_DEPTH).clamp(1, MAX_DEPTH);
+
+        for depth in 1..=max_depth {
+            self.previous_variation = result.principal_variation.clone();
+            self.on_previous_variation = true;
+
+            let mut principal_variation = Vec::new();
+            let score = self.negamax(
+                &mut game_state,
+                depth,
+                0,
+                -INFINITY,
+                INFINITY,
+                &mut principal_variation,
+            );
+
+            if self.stopped && result.best_move.is_some() {
+                break;
+            }
+
+            result = SearchResult {
+                best_move: principal_variation.first().copied(),
+                score,
+                depth,
+                nodes: self.nodes,
+                principal_variation,
+            };
+            progress(&result);
+
+            // A forced mate cannot be improved on by searching deeper
+            if self.stopped || score.abs() > MATE_BOUND {
+                break;
+            }
+        }
+
+        // Play something rather than nothing when the limits leave no time for a single iteration
+        if result.best_move.is_none() {
+            let mut moves = game_state.legal_moves();
+            order_moves(&game_state, &mut moves, None);
+            result.best_move = moves.first().copied();
+        }
+
+        result.nodes = self.nodes;
+        result
+    }
+
+    fn negamax(
+        &mut self,
+        game_state: &mut GameState,
+        depth: usize,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+        principal_variation: &mut Vec<Move>,
+    ) -> i32 {
+        self.nodes += 1;
+        if self.should_stop() {
+            return 0;
+        }
+
+        if ply > 0 && is_draw(game_state) {
+            return 0;
+        }
+        if depth == 0 || ply >= MAX_PLY {
+            return self.quiescence(game_state, ply, alpha, beta);
+        }
+
+        let key = game_state.zobrist_key();
+        let entry = self.transposition_table.probe(key).copied();
+        // The root is always searched, so that it has a best move
+        if let Some(entry) = entry
+            && ply > 0
+            && entry.depth >= depth
+        {
+            let score = entry.score_at_ply(ply);
+            let usable = match entry.bound {
+                Bound::Exact => true,
+                Bound::Lower => score >= beta,
+                Bound::Upper => score <= alpha,
+            };
+            if usable {
+                return score;
+            }
+        }
+
+        let mut moves = game_state.legal_moves();
+        if moves.is_empty() {
+            return match game_state.is_check() {
+                true => -MATE_SCORE + ply as i32,
+                false => 0,
+            };
+        }
+        let previous_best = match self.on_previous_variation {
+            true => self.previous_variation.get(ply).copied(),
+            false => None,
+        }
+        .or(entry.and_then(|entry| entry.best_move));
+        order_moves(game_state, &mut moves, previous_best);
+
+        let original_alpha = alpha;
+        let mut best_move = None;
+
+        let mut child_variation = Vec::new();
+        for next_move in moves {
+            self.on_previous_variation = previous_best == Some(next_move);
+
+            child_variation.clear();
+            game_state.make_move(next_move);
+            let score = -self.negamax(
+                game_state,
+                depth - 1,
+                ply + 1,
+                -beta,
+                -alpha,
+                &mut child_variation,
+            );
+            game_state.unmake_move();
+
+            if self.stopped {
+                return 0;
+            }
+
+            if score > alpha {
+                alpha = score;
+                best_move = Some(next_move);
+                principal_variation.clear();
+                principal_variation.push(next_move);
+                principal_variation.extend_from_slice(&child_variation);
+
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+
+        let bound = if alpha >= beta {
+            Bound::Lower
+        }
+        else if alpha > original_alpha {
+            Bound::Exact
+        }
+        else {
+            Bound::Upper
+        };
+        self.transposition_table
+            .store(key, depth, ply, bound, alpha, best_move);
+
+        alpha
+    }
+
+    /// Resolve captures until the position is quiet, so that the evaluation does not stop in the
+    /// middle of an exchange. The side to move may also stand pat instead of capturing, unless it
+    /// is in check: every evasion is then searched, and checkmate and stalemate are scored.
+    fn quiescence(
+        &mut self,
+        game_state: &mut GameState,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        self.nodes += 1;
+        if self.should_stop() {
+            return 0;
+        }
+        if ply >= MAX_PLY {
+            return evaluate(game_state);
+        }
+
+        let in_check = game_state.is_check();
+        let mut captures = game_state.legal_moves();
+        if captures.is_empty() {
+            return match in_check {
+                true => -MATE_SCORE + ply as i32,
+                false => 0,
+            };
+        }
+
+        if !in_check {
+            let stand_pat = evaluate(game_state);
+            if stand_pat >= beta {
+                return stand_pat;
+            }
+            alpha = alpha.max(stand_pat);
+
+            captures
+                .retain(|next_move| is_capture(game_state, *next_move) || next_move.is_promotion());
+        }
+        order_moves(game_state, &mut captures, None);
+
+        for next_move in captures {
+            game_state.make_move(next_move);
+            let score = -self.quiescence(game_state, ply + 1, -beta, -alpha);
+            game_state.unmake_move();
+
+            if self.stopped {
+                return 0;
+            }
+
+            if score > alpha {
+                alpha = score;
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+
+        alpha
+    }
+
+    fn should_stop(&mut self) -> bool {
+        if self.stopped {
+            return true;
+        }
+
+        if self.stop_signal.load(Ordering::Relaxed)
+            || self.limits.nodes.is_some_and(|nodes| self.nodes >= nodes)
+            || (self.nodes.is_multiple_of(TIME_CHECK_INTERVAL)
+                && self
+                    .limits
+                    .time
+                    .is_some_and(|time| self.start.elapsed() >= time))
+        {
+            self.stopped = true;
+        }
+
+        self.stopped
+    }
+}
+
+/// Draws the search can rely on: a repetition, the fifty-move rule or insufficient material.
+/// A single repetition is enough, as repeating it again cannot be better.
+fn is_draw(game_state: &GameState) -> bool {
+    game_state.repetition_count() >= 2
+        || game_state.can_claim_fifty_move_draw()
+        || game_state.is_insufficient_material()
+}
+
+fn is_capture(game_state: &GameState, next_move: Move) -> bool {
+    game_state
+        .board()
+        .is_position_occupied(next_move.capture_position())
+}
+
+/// Sort moves so that cutoffs happen early: the best move already known first, then
+/// captures of the most valuable victims by the least valuable attackers, then promotions
+fn order_moves(game_state: &GameState, moves: &mut [Move], best_move: Option<Move>) {
+    let board = game_state.board();
+
+    moves.sort_by_cached_key(|next_move| {
+        if Some(*next_move) == best_move {
+            return i32::MIN;
+        }
+
+        let victim = board
+            .get_piece(next_move.capture_position())
+            .map_or(0, |piece| piece_value(&piece.piece_type));
+        let attacker = board
+            .get_piece(next_move.source())
+            .map_or(0, |piece| piece_value(&piece.piece_type));
+        let promotion = next_move
+            .promotion
+            .map_or(0, |piece_type| piece_value(&piece_type));
+
+        match victim {
+            0 => -promotion,
+            _ => -(10 * victim - attacker + promotion),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pieces::PieceType;
+    use crate::pieces::types::Rook;
+
+    fn best_move(fen: &str, limits: SearchLimits) -> SearchResult {
+        Engine::new().search(&GameState::from_fen(fen).unwrap(), limits)
+    }
+
+    #[test]
+    fn test_finds_mate_in_one() {
+        let result = best_move("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", SearchLimits::depth(3));
+
+        assert_eq!(result.best_move.unwrap().to_string(), "a1a8");
+        assert_eq!(result.mate_in(), Some(1));
+        assert!(result.depth < 3);
+    }
+
+    #[test]
+    fn test_finds_mate_in_one_at_depth_one() {
+        // The mate is on the horizon: the reply is only searched in quiescence
+        let result = best_move("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1", SearchLimits::depth(1));
+
+        assert_eq!(result.best_move.unwrap().to_string(), "a1a8");
+        assert_eq!(result.mate_in(), Some(1));
+    }
+
+    #[test]
+    fn test_quiescence_scores_stalemate() {
+        let mut game_state = GameState::from_fen("7k/5Q2/8/8/8/8/8/K7 b - - 0 1").unwrap();
+
+        assert_eq!(
+            Engine::new().quiescence(&mut game_state, 1, -MATE_SCORE, MATE_SCORE),
+            0
+        );
+    }
+
+    #[test]
+    fn test_finds_mate_in_two() {
+        // The King takes the opposition on b6 or c7, so that the Rook mates on the next move
+        let result = best_move("k7/8/2K5/8/8/8/8/7R w - - 0 1", SearchLimits::depth(4));
+
+        assert_eq!(result.mate_in(), Some(2));
+        assert_eq!(result.principal_variation.len(), 3);
+    }
+
+    #[test]
+    fn test_sees_being_mated() {
+        let result = best_move(
+            "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3",
+            SearchLimits::depth(2),
+        );
+
+        assert_eq!(result.best_move, None);
+        assert_eq!(result.score, -MATE_SCORE);
+    }
+
+    #[test]
+    fn test_wins_hanging_queen() {
+        let result = best_move("4k3/8/8/3q4/8/8/8/3QK3 w - - 0 1", SearchLimits::depth(2));
+
+        assert_eq!(result.best_move.unwrap().to_string(), "d1d5");
+        assert!(result.score > piece_value(&PieceType::Rook(Rook::new())));
+    }
+
+    #[test]
+    fn test_quiescence_avoids_defended_pawn() {
+        // Taking on d5 loses the Queen to the pawn on e6
+        let result = best_move("4k3/8/4p3/3p4/8/8/8/3QK3 w - - 0 1", SearchLimits::depth(1));
+
+        assert_ne!(result.best_move.unwrap().to_string(), "d1d5");
+    }
+
+    #[test]
+    fn test_stalemate_is_a_draw() {
+        let result = best_move("7k/5Q2/8/8/8/8/8/K7 b - - 0 1", SearchLimits::depth(3));
+
+        assert_eq!(result.best_move, None);
+        assert_eq!(result.score, 0);
+    }
+
+    #[test]
+    fn test_node_and_time_limits() {
+        let game_state = GameState::starting_position();
+        let mut engine = Engine::new();
+
+        let result = engine.search(&game_state, SearchLimits::nodes(2000));
+        assert!(result.nodes <= 2000);
+        assert!(result.best_move.is_some());
+
+        let start = Instant::now();
+        let result = engine.search(&game_state, SearchLimits::time(Duration::from_millis(100)));
+        assert!(start.elapsed() < Duration::from_millis(1000));
+        assert!(result.best_move.is_some());
+        assert!(
+            game_state
+                .legal_moves()
+                .contains(&result.best_move.unwrap())
+        );
+    }
+
+    #[test]
+    fn test_search_leaves_game_state_untouched() {
+        let game_state = GameState::starting_position();
+
+        Engine::new().search(&game_state, SearchLimits::depth(3));
+
+        assert_eq!(game_state, GameState::starting_position());
+    }
+
+    #[test]
+    fn test_transposition_table_saves_nodes() {
+        let game_state = GameState::starting_position();
+
+        let with_table = Engine::new().search(&game_state, SearchLimits::depth(4));
+        let without_table = Engine::with_hash_size(0).search(&game_state, SearchLimits::depth(4));
+
+        assert!(with_table.nodes < without_table.nodes);
+    }
+
+    #[test]
+    fn test_mate_found_through_the_transposition_table() {
+        let game_state = GameState::from_fen("k7/8/2K5/8/8/8/8/7R w - - 0 1").unwrap();
+        let mut engine = Engine::new();
+
+        let first = engine.search(&game_state, SearchLimits::depth(4));
+        // The second search starts from the stored entries, whose mate scores count from the root
+        let second = engine.search(&game_state, SearchLimits::depth(4));
+
+        assert_eq!(second.mate_in(), Some(2));
+        assert!(second.nodes < first.nodes);
+
+        engine.new_game();
+        assert_eq!(engine.search(&game_state, SearchLimits::depth(4)), first);
+    }
+
+    #[test]
+    fn test_progress_after_each_iteration() {
+        let mut depths = Vec::new();
+
+        let result = Engine::new().search_with_progress(
+            &GameState::starting_position(),
+            SearchLimits::depth(3),
+            |result| depths.push(result.depth),
+        );
+
+        assert_eq!(depths, [1, 2, 3]);
+        assert_eq!(result.depth, 3);
+    }
+
+    #[test]
+    fn test_stop_signal() {
+        let game_state = GameState::starting_position();
+        let mut engine = Engine::new();
+
+        engine.stop_signal().store(true, Ordering::Relaxed);
+        let result = engine.search(&game_state, SearchLimits::default());
+
+        // Stopped before the first iteration completed, the engine still plays a legal move
+        assert!(result.nodes <= 1);
+        assert!(
+            game_state
+                .legal_moves()
+                .contains(&result.best_move.unwrap())
+        );
+    }
+}
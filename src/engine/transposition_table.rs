@@ -0,0 +1,250 @@
+//! Fixed-size hash table of searched positions, indexed by their Zobrist key, so that positions
+//! reached again through another move order are not searched twice
+
+use crate::engine::MATE_BOUND;
+use crate::movement::Move;
+use std::mem::size_of;
+
+/// Size of the table created by [`TranspositionTable::default`]
+pub const DEFAULT_SIZE_MB: usize = 16;
+
+const BYTES_PER_MB: usize = 1024 * 1024;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+/// How a stored score relates to the real score of the position
+pub enum Bound {
+    /// Every move was searched inside the window: the score is the real one
+    Exact,
+    /// A move failed high: the real score is at least the stored one
+    Lower,
+    /// No move raised alpha: the real score is at most the stored one
+    Upper,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+/// Result of the search of a position
+pub struct Entry {
+    pub key: u64,
+    pub depth: usize,
+    pub bound: Bound,
+    /// Score from the point of view of the side to move, mates counted from this position
+    pub score: i32,
+    pub best_move: Option<Move>,
+    /// Search the entry was stored in, to replace the entries of previous searches first
+    generation: u8,
+}
+
+impl Entry {
+    /// Score seen from the root of the current search, `ply` plies above this position
+    pub fn score_at_ply(&self, ply: usize) -> i32 {
+        if self.score > MATE_BOUND {
+            self.score - ply as i32
+        }
+        else if self.score < -MATE_BOUND {
+            self.score + ply as i32
+        }
+        else {
+            self.score
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+/// Table of [`Entry`], with one slot per key: an entry is replaced by a deeper search of any
+/// position, by any search of the same position, and by anything once a new search started
+pub struct TranspositionTable {
+    entries: Vec<Option<Entry>>,
+    generation: u8,
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        Self::new(DEFAULT_SIZE_MB)
+    }
+}
+
+impl TranspositionTable {
+    /// Create a table taking up at most `size_mb` megabytes, rounded down to a power of two
+    /// number of entries. A size of 0 disables the table.
+    pub fn new(size_mb: usize) -> Self {
+        let capacity = size_mb * BYTES_PER_MB / size_of::<Option<Entry>>();
+        let entries = match capacity {
+            0 => 0,
+            _ => 1 << capacity.ilog2(),
+        };
+
+        Self {
+            entries: vec![None; entries],
+            generation: 0,
+        }
+    }
+
+    /// Number of positions the table can hold
+    pub fn capacity(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Forget every position, e.g. before a new game
+    pub fn clear(&mut self) {
+        self.entries.fill(None);
+        self.generation = 0;
+    }
+
+    /// Mark the entries stored so far as belonging to a previous search
+    pub fn new_search(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    pub fn probe(&self, key: u64) -> Option<&Entry> {
+        let index = self.index(key)?;
+
+        self.entries[index]
+            .as_ref()
+            .filter(|entry| entry.key == key)
+    }
+
+    /// Store the result of a search `ply` plies below the root, converting mate scores so that
+    /// they count from the stored position
+    pub fn store(
+        &mut self,
+        key: u64,
+        depth: usize,
+        ply: usize,
+        bound: Bound,
+        score: i32,
+        best_move: Option<Move>,
+    ) {
+        let Some(index) = self.index(key)
+        else {
+            return;
+        };
+
+        let generation = self.generation;
+        let replace = self.entries[index].is_none_or(|entry| {
+            entry.key == key || entry.generation != generation || depth >= entry.depth
+        });
+        if !replace {
+            return;
+        }
+
+        let score = if score > MATE_BOUND {
+            score + ply as i32
+        }
+        else if score < -MATE_BOUND {
+            score - ply as i32
+        }
+        else {
+            score
+        };
+        // Keep the known best move when a search failing low found none
+        let best_move = best_move.or_else(|| {
+            self.entries[index]
+                .filter(|entry| entry.key == key)
+                .and_then(|entry| entry.best_move)
+        });
+
+        self.entries[index] = Some(Entry {
+            key,
+            depth,
+            bound,
+            score,
+            best_move,
+            generation,
+        });
+    }
+
+    fn index(&self, key: u64) -> Option<usize> {
+        match self.entries.len() {
+            0 => None,
+            len => Some(key as usize & (len - 1)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::MATE_SCORE;
+
+    #[test]
+    fn test_size() {
+        let table = TranspositionTable::new(1);
+
+        assert!(table.capacity().is_power_of_two());
+        assert!(table.capacity() * size_of::<Option<Entry>>() <= BYTES_PER_MB);
+        assert_eq!(TranspositionTable::new(0).capacity(), 0);
+    }
+
+    #[test]
+    fn test_store_and_probe() {
+        let mut table = TranspositionTable::new(1);
+        let other_key = 42 + table.capacity() as u64;
+
+        table.store(42, 3, 0, Bound::Exact, 25, None);
+
+        let entry = table.probe(42).unwrap();
+        assert_eq!(
+            (entry.depth, entry.bound, entry.score),
+            (3, Bound::Exact, 25)
+        );
+        // Same slot, different position
+        assert_eq!(table.probe(other_key), None);
+    }
+
+    #[test]
+    fn test_disabled_table_stores_nothing() {
+        let mut table = TranspositionTable::new(0);
+
+        table.store(42, 3, 0, Bound::Exact, 25, None);
+
+        assert_eq!(table.probe(42), None);
+    }
+
+    #[test]
+    fn test_replacement_policy() {
+        let mut table = TranspositionTable::new(1);
+        let other_key = 42 + table.capacity() as u64;
+
+        table.store(42, 5, 0, Bound::Exact, 25, None);
+        // A shallower search of another position does not evict a deeper one
+        table.store(other_key, 2, 0, Bound::Exact, 10, None);
+        assert!(table.probe(42).is_some());
+
+        // The same position is always updated
+        table.store(42, 1, 0, Bound::Lower, 30, None);
+        assert_eq!(table.probe(42).unwrap().depth, 1);
+
+        // Entries of previous searches are evicted first
+        table.store(42, 5, 0, Bound::Exact, 25, None);
+        table.new_search();
+        table.store(other_key, 2, 0, Bound::Exact, 10, None);
+        assert_eq!(table.probe(42), None);
+        assert!(table.probe(other_key).is_some());
+    }
+
+    #[test]
+    fn test_mate_scores_are_relative_to_the_position() {
+        let mut table = TranspositionTable::new(1);
+
+        // Mate 5 plies from the root, found 2 plies below it: mate in 3 plies from there
+        table.store(42, 4, 2, Bound::Exact, MATE_SCORE - 5, None);
+
+        let entry = table.probe(42).unwrap();
+        assert_eq!(entry.score, MATE_SCORE - 3);
+        assert_eq!(entry.score_at_ply(2), MATE_SCORE - 5);
+        assert_eq!(entry.score_at_ply(6), MATE_SCORE - 9);
+
+        table.store(43, 4, 3, Bound::Exact, -MATE_SCORE + 7, None);
+        assert_eq!(table.probe(43).unwrap().score_at_ply(1), -MATE_SCORE + 5);
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut table = TranspositionTable::new(1);
+
+        table.store(42, 3, 0, Bound::Exact, 25, None);
+        table.clear();
+
+        assert_eq!(table.probe(42), None);
+    }
+}
@@ -1,9 +1,15 @@
 pub mod constants;
+mod fen_error;
 mod move_error;
 mod pgn_error;
+mod pgn_read_error;
 mod position_errors;
+mod setup_error;
 
+pub use fen_error::FenError;
 pub use move_error::MoveError;
 pub use pgn_error::PgnError;
+pub use pgn_read_error::PgnReadError;
 pub use position_errors::ChessPositionError;
 pub use position_errors::PositionError;
+pub use setup_error::{SetupError, SetupErrorKind};
@@ -8,3 +8,7 @@ pub const INTERNAL_ERROR_05: &str = "Internal error 05: invalid enum variant for
 pub const INTERNAL_ERROR_06: &str = "Internal error 06: the provided board position has a different piece type than the one expected";
 pub const INTERNAL_ERROR_07: &str =
     "Internal error 06: the provided board position has no piece in it, where one is expected";
+pub const INTERNAL_ERROR_08: &str = "Internal error 08: the built-in starting position is invalid";
+pub const INTERNAL_ERROR_09: &str = "Internal error 09: the engine chose an illegal move";
+pub const INTERNAL_ERROR_10: &str =
+    "Internal error 10: the engine is missing although no search is running";
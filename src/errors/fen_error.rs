@@ -0,0 +1,39 @@
+use crate::errors::SetupErrorKind;
+use std::error::Error;
+use std::fmt::Display;
+
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub enum FenError {
+    MissingField(&'static str),
+    TooManyFields,
+    InvalidRankCount(usize),
+    InvalidRank(char),
+    InvalidPiece(char),
+    InvalidActiveColor,
+    InvalidCastlingRights(char),
+    InvalidEnPassantSquare,
+    InvalidClock(&'static str),
+    IllegalPosition(SetupErrorKind),
+}
+
+impl Error for FenError {}
+
+impl Display for FenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingField(field) => write!(f, "Missing {field} field"),
+            Self::TooManyFields => write!(f, "Too many fields"),
+            Self::InvalidRankCount(count) => write!(f, "Expected 8 ranks, found {count}"),
+            Self::InvalidRank(rank) => write!(f, "Rank {rank} does not describe 8 squares"),
+            Self::InvalidPiece(piece) => write!(f, "Invalid piece character: {piece}"),
+            Self::InvalidActiveColor => write!(f, "The active color should be 'w' or 'b'"),
+            Self::InvalidCastlingRights(right) => {
+                write!(f, "Invalid castling right: {right}")
+            }
+            Self::InvalidEnPassantSquare => write!(f, "Invalid en passant square"),
+            Self::InvalidClock(clock) => write!(f, "Invalid {clock} clock"),
+            Self::IllegalPosition(kind) => write!(f, "Illegal position: {kind}"),
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use std::error::Error;
 use std::fmt::Display;
 
+use crate::pgn::constants::PROMOTION;
+
 use super::{ChessPositionError, PgnError, PositionError};
 
 #[derive(Debug, PartialEq)]
@@ -14,6 +16,10 @@ pub enum MoveError {
     InvalidMove(&'static str),
     InvalidPgn(PgnError),
     InvalidCastle(&'static str),
+    MissingPromotion,
+    InvalidPromotion(&'static str),
+    InvalidCheck(&'static str),
+    GameOver,
 }
 
 impl Error for MoveError {}
@@ -33,6 +39,13 @@ impl Display for MoveError {
             Self::InvalidMove(err) => write!(f, "Invalid move: {err}"),
             Self::InvalidPgn(err) => write!(f, "Invalid PGN: {err}"),
             Self::InvalidCastle(err) => write!(f, "Unable to castle: {err}"),
+            Self::MissingPromotion => write!(
+                f,
+                "Invalid move: a pawn reaching the last rank must be promoted (e.g., e8{PROMOTION}Q)",
+            ),
+            Self::InvalidPromotion(err) => write!(f, "Invalid promotion: {err}"),
+            Self::InvalidCheck(err) => write!(f, "Invalid check: {err}"),
+            Self::GameOver => write!(f, "Invalid move: the game is over"),
         }
     }
 }
@@ -0,0 +1,59 @@
+use crate::errors::{FenError, MoveError};
+use std::error::Error;
+use std::fmt::Display;
+use std::io::ErrorKind;
+
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub enum PgnReadError {
+    /// The PGN text could not be read
+    Io(ErrorKind),
+    /// Malformed tag pair, on the given line
+    InvalidTag(usize),
+    /// Comment opened on the given line is never closed
+    UnterminatedComment(usize),
+    /// Variation parenthesis without its counterpart, on the given line
+    UnbalancedVariation(usize),
+    /// Game, numbered from 1, whose movetext does not end with a result token
+    MissingResult(usize),
+    /// The FEN tag of a game, numbered from 1, does not describe a valid position
+    InvalidFen { game: usize, error: FenError },
+    /// A move of a game could not be played; both game and ply are numbered from 1
+    IllegalMove {
+        game: usize,
+        ply: usize,
+        san: String,
+        error: MoveError,
+    },
+}
+
+impl Error for PgnReadError {}
+
+impl Display for PgnReadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(kind) => write!(f, "Could not read PGN: {kind}"),
+            Self::InvalidTag(line) => write!(f, "Line {line}: invalid tag pair"),
+            Self::UnterminatedComment(line) => {
+                write!(f, "Line {line}: comment is never closed")
+            }
+            Self::UnbalancedVariation(line) => {
+                write!(f, "Line {line}: unbalanced variation parentheses")
+            }
+            Self::MissingResult(game) => write!(f, "Game {game}: missing result token"),
+            Self::InvalidFen { game, error } => write!(f, "Game {game}: invalid FEN tag: {error}"),
+            Self::IllegalMove {
+                game,
+                ply,
+                san,
+                error,
+            } => write!(f, "Game {game}, ply {ply} ({san}): {error}"),
+        }
+    }
+}
+
+impl From<std::io::Error> for PgnReadError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error.kind())
+    }
+}
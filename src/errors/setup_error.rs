@@ -0,0 +1,100 @@
+use crate::errors::ChessPositionError;
+use crate::pieces::Color;
+use std::error::Error;
+use std::fmt::Display;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub enum SetupError {
+    /// The positions could not be read, from the given file when they come from one
+    Io {
+        path: Option<PathBuf>,
+        kind: ErrorKind,
+    },
+    /// A line of the positions file is invalid; lines are numbered from 1
+    InvalidLine(usize, SetupErrorKind),
+    /// The positions are well-formed but do not describe a legal chess position
+    IllegalPosition(SetupErrorKind),
+}
+
+#[derive(Debug, PartialEq)]
+#[non_exhaustive]
+pub enum SetupErrorKind {
+    IncompleteLine,
+    TrailingCharacters(String),
+    InvalidColor(char),
+    InvalidPiece(char),
+    InvalidSquare(ChessPositionError),
+    DuplicateSquare,
+    PawnOnBackRank,
+    MissingKing(Color),
+    TooManyKings(Color),
+    OpponentInCheck(Color),
+}
+
+impl Error for SetupError {}
+
+impl Display for SetupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io {
+                path: Some(path),
+                kind,
+            } => write!(
+                f,
+                "Could not read positions from {}: {kind}",
+                path.display()
+            ),
+            Self::Io { path: None, kind } => write!(f, "Could not read positions: {kind}"),
+            Self::InvalidLine(line, kind) => write!(f, "Line {line}: {kind}"),
+            Self::IllegalPosition(kind) => write!(f, "Illegal position: {kind}"),
+        }
+    }
+}
+
+impl From<std::io::Error> for SetupError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io {
+            path: None,
+            kind: error.kind(),
+        }
+    }
+}
+
+impl SetupError {
+    /// Name the file the positions were read from in an I/O error
+    pub(crate) fn in_file(self, path: &Path) -> Self {
+        match self {
+            Self::Io { kind, .. } => Self::Io {
+                path: Some(path.to_path_buf()),
+                kind,
+            },
+            other => other,
+        }
+    }
+}
+
+impl Error for SetupErrorKind {}
+
+impl Display for SetupErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IncompleteLine => write!(f, "Expected a color, a piece and a square"),
+            Self::TrailingCharacters(rest) => {
+                write!(f, "Unexpected characters after the square: {rest}")
+            }
+            Self::InvalidColor(color) => write!(f, "Invalid color character: {color}"),
+            Self::InvalidPiece(piece) => write!(f, "Invalid piece character: {piece}"),
+            Self::InvalidSquare(error) => write!(f, "Invalid square: {error}"),
+            Self::DuplicateSquare => write!(f, "Square is already occupied"),
+            Self::PawnOnBackRank => write!(f, "Pawns cannot stand on the first or last rank"),
+            Self::MissingKing(color) => write!(f, "{color} has no king"),
+            Self::TooManyKings(color) => write!(f, "{color} has more than one king"),
+            Self::OpponentInCheck(color) => {
+                write!(f, "{color} is in check but it is not their turn")
+            }
+        }
+    }
+}
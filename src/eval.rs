@@ -0,0 +1,475 @@
+//! Static evaluation of a position, in centipawns from the point of view of the side to move.
+//! Every term is scored separately for the middlegame and the endgame, then blended according to
+//! the material left on the board.
+
+mod tables;
+
+use crate::board::constants::BOARD_SIZE;
+use crate::board::square;
+use crate::movement::Position;
+use crate::pieces::types::{King, Pawn};
+use crate::pieces::{Color, Piece, PieceType};
+use crate::{Board, GameState};
+use std::fmt::Display;
+use std::ops::{Add, AddAssign, Mul, Sub};
+
+/// Game phase with every minor and major piece on the board, down to 0 with none left
+pub const MAX_PHASE: i32 = 24;
+
+const PAWN_VALUE: Score = Score::new(82, 94);
+const KNIGHT_VALUE: Score = Score::new(337, 281);
+const BISHOP_VALUE: Score = Score::new(365, 297);
+const ROOK_VALUE: Score = Score::new(477, 512);
+const QUEEN_VALUE: Score = Score::new(1025, 936);
+
+const DOUBLED_PAWN: Score = Score::new(-10, -20);
+const ISOLATED_PAWN: Score = Score::new(-10, -15);
+/// Bonus of a passed pawn by the number of squares it advanced from its starting rank
+const PASSED_PAWN: [Score; 6] = [
+    Score::new(0, 5),
+    Score::new(5, 10),
+    Score::new(10, 20),
+    Score::new(20, 40),
+    Score::new(35, 70),
+    Score::new(60, 120),
+];
+
+/// Bonus for each square a piece attacks that is not occupied by its own side
+const KNIGHT_MOBILITY: Score = Score::new(4, 4);
+const BISHOP_MOBILITY: Score = Score::new(5, 5);
+const ROOK_MOBILITY: Score = Score::new(2, 4);
+const QUEEN_MOBILITY: Score = Score::new(1, 2);
+
+/// Bonus for each pawn standing in front of the King, on its file or an adjacent one
+const PAWN_SHIELD: Score = Score::new(10, 0);
+/// Penalty for each square next to the King that the opponent attacks
+const KING_ZONE_ATTACK: Score = Score::new(-8, -2);
+
+const FILE_A: u64 = 0x0101_0101_0101_0101;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+/// Score of a term in the middlegame and in the endgame
+struct Score {
+    mg: i32,
+    eg: i32,
+}
+
+impl Score {
+    const fn new(mg: i32, eg: i32) -> Self {
+        Self { mg, eg }
+    }
+
+    /// Interpolate between the middlegame and the endgame scores
+    fn blend(self, phase: i32) -> i32 {
+        (self.mg * phase + self.eg * (MAX_PHASE - phase)) / MAX_PHASE
+    }
+}
+
+impl Add for Score {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.mg + other.mg, self.eg + other.eg)
+    }
+}
+
+impl AddAssign for Score {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Score {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.mg - other.mg, self.eg - other.eg)
+    }
+}
+
+impl Mul<i32> for Score {
+    type Output = Self;
+
+    fn mul(self, factor: i32) -> Self {
+        Self::new(self.mg * factor, self.eg * factor)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+/// Evaluation split by term, each in centipawns from the point of view of the side to move
+pub struct Evaluation {
+    pub material: i32,
+    pub piece_squares: i32,
+    pub pawn_structure: i32,
+    pub mobility: i32,
+    pub king_safety: i32,
+    /// Game phase the terms were blended with, from [`MAX_PHASE`] in the opening to 0 in pawn
+    /// endgames
+    pub phase: i32,
+}
+
+impl Evaluation {
+    pub fn total(&self) -> i32 {
+        self.material + self.piece_squares + self.pawn_structure + self.mobility + self.king_safety
+    }
+}
+
+impl Display for Evaluation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Material:       {:>6}", self.material)?;
+        writeln!(f, "Piece-squares:  {:>6}", self.piece_squares)?;
+        writeln!(f, "Pawn structure: {:>6}", self.pawn_structure)?;
+        writeln!(f, "Mobility:       {:>6}", self.mobility)?;
+        writeln!(f, "King safety:    {:>6}", self.king_safety)?;
+        writeln!(f, "Phase:          {:>6}", self.phase)?;
+        write!(f, "Total:          {:>6}", self.total())
+    }
+}
+
+/// Score of the position in centipawns, from the point of view of the side to move
+pub fn evaluate(game_state: &GameState) -> i32 {
+    evaluate_terms(game_state).total()
+}
+
+/// Score of the position split by term, to see where an evaluation comes from and tune it
+pub fn evaluate_terms(game_state: &GameState) -> Evaluation {
+    let board = game_state.board();
+    let phase = phase(board);
+    let perspective = match game_state.is_white_turn() {
+        true => 1,
+        false => -1,
+    };
+    let term = |score: fn(&Board, Color) -> Score| {
+        (score(board, Color::White) - score(board, Color::Black)).blend(phase) * perspective
+    };
+
+    Evaluation {
+        material: term(material),
+        piece_squares: term(piece_squares),
+        pawn_structure: term(pawn_structure),
+        mobility: term(mobility),
+        king_safety: term(king_safety),
+        phase,
+    }
+}
+
+/// Middlegame value of a piece, enough to tell which captures are worth trying first
+pub fn piece_value(piece_type: &PieceType) -> i32 {
+    match piece_type {
+        PieceType::King(_) | PieceType::None => 0,
+        _ => value(piece_type).mg,
+    }
+}
+
+fn value(piece_type: &PieceType) -> Score {
+    match piece_type {
+        PieceType::Pawn(_) => PAWN_VALUE,
+        PieceType::Knight => KNIGHT_VALUE,
+        PieceType::Bishop => BISHOP_VALUE,
+        PieceType::Rook(_) => ROOK_VALUE,
+        PieceType::Queen => QUEEN_VALUE,
+        PieceType::King(_) | PieceType::None => Score::default(),
+    }
+}
+
+fn phase(board: &Board) -> i32 {
+    let phase = board
+        .into_iter()
+        .map(|(piece, _)| match piece.piece_type {
+            PieceType::Knight | PieceType::Bishop => 1,
+            PieceType::Rook(_) => 2,
+            PieceType::Queen => 4,
+            _ => 0,
+        })
+        .sum::<i32>();
+
+    phase.min(MAX_PHASE)
+}
+
+fn pieces(board: &Board, color: Color) -> impl Iterator<Item = (Piece, Position)> {
+    board
+        .into_iter()
+        .filter(move |(piece, _)| piece.color == color)
+}
+
+fn material(board: &Board, color: Color) -> Score {
+    pieces(board, color).fold(Score::default(), |score, (piece, _)| {
+        score + value(&piece.piece_type)
+    })
+}
+
+fn piece_squares(board: &Board, color: Color) -> Score {
+    pieces(board, color).fold(Score::default(), |score, (piece, position)| {
+        // Black pieces read the tables upside down
+        let index = match color {
+            Color::White => square(position),
+            Color::Black => square(position) ^ 56,
+        };
+        let (mg_table, eg_table) = match piece.piece_type {
+            PieceType::Pawn(_) => (&tables::PAWN_MG, &tables::PAWN_EG),
+            PieceType::Knight => (&tables::KNIGHT, &tables::KNIGHT),
+            PieceType::Bishop => (&tables::BISHOP, &tables::BISHOP),
+            PieceType::Rook(_) => (&tables::ROOK, &tables::ROOK),
+            PieceType::Queen => (&tables::QUEEN, &tables::QUEEN),
+            PieceType::King(_) => (&tables::KING_MG, &tables::KING_EG),
+            PieceType::None => return score,
+        };
+
+        score + Score::new(mg_table[index], eg_table[index])
+    })
+}
+
+fn pawn_structure(board: &Board, color: Color) -> Score {
+    let pawns = board.pieces_of_type(&PieceType::Pawn(Pawn::new()), color);
+    let enemy_pawns = board.pieces_of_type(&PieceType::Pawn(Pawn::new()), color.opposite());
+    let mut score = Score::default();
+
+    for col in 0..BOARD_SIZE {
+        let file_pawns = (pawns & file_mask(col)).count_ones() as i32;
+        if file_pawns > 1 {
+            score += DOUBLED_PAWN * (file_pawns - 1);
+        }
+        if file_pawns > 0 && pawns & adjacent_files_mask(col) == 0 {
+            score += ISOLATED_PAWN * file_pawns;
+        }
+    }
+
+    for position in Board::positions(pawns) {
+        let front_span = ahead_mask(color, position.line)
+            & (file_mask(position.col) | adjacent_files_mask(position.col));
+        if enemy_pawns & front_span == 0 {
+            let advancement = match color {
+                Color::White => BOARD_SIZE - 2 - position.line,
+                Color::Black => position.line - 1,
+            };
+            score += PASSED_PAWN[advancement];
+        }
+    }
+
+    score
+}
+
+fn mobility(board: &Board, color: Color) -> Score {
+    let own_pieces = board.pieces(color);
+
+    pieces(board, color).fold(Score::default(), |score, (piece, position)| {
+        let weight = match piece.piece_type {
+            PieceType::Knight => KNIGHT_MOBILITY,
+            PieceType::Bishop => BISHOP_MOBILITY,
+            PieceType::Rook(_) => ROOK_MOBILITY,
+            PieceType::Queen => QUEEN_MOBILITY,
+            _ => return score,
+        };
+        let squares = (board.attacks_from(&piece, position) & !own_pieces).count_ones() as i32;
+
+        score + weight * squares
+    })
+}
+
+fn king_safety(board: &Board, color: Color) -> Score {
+    let king_type = PieceType::King(King::new());
+    let Some(king_position) = Board::positions(board.pieces_of_type(&king_type, color)).next()
+    else {
+        return Score::default();
+    };
+    let king_zone = board.attacks_from(&Piece::new(king_type, color), king_position);
+
+    let shield_span = ahead_mask(color, king_position.line)
+        & (file_mask(king_position.col) | adjacent_files_mask(king_position.col))
+        & (king_zone | shift_forward(color, king_zone));
+    let shield = (board.pieces_of_type(&PieceType::Pawn(Pawn::new()), color) & shield_span)
+        .count_ones() as i32;
+
+    let attacked = Board::positions(king_zone)
+        .filter(|position| board.is_square_attacked(*position, color.opposite()))
+        .count() as i32;
+
+    PAWN_SHIELD * shield + KING_ZONE_ATTACK * attacked
+}
+
+fn file_mask(col: usize) -> u64 {
+    FILE_A << col
+}
+
+fn adjacent_files_mask(col: usize) -> u64 {
+    let left = if col > 0 { file_mask(col - 1) } else { 0 };
+    let right = if col + 1 < BOARD_SIZE {
+        file_mask(col + 1)
+    }
+    else {
+        0
+    };
+
+    left | right
+}
+
+/// Every square on the lines in front of the given line, as seen by the given color
+fn ahead_mask(color: Color, line: usize) -> u64 {
+    match color {
+        Color::White => (1 << (line * BOARD_SIZE)) - 1,
+        Color::Black if line + 1 < BOARD_SIZE => u64::MAX << ((line + 1) * BOARD_SIZE),
+        Color::Black => 0,
+    }
+}
+
+/// Shift a bitboard one line further forward for the given color
+fn shift_forward(color: Color, bitboard: u64) -> u64 {
+    match color {
+        Color::White => bitboard >> BOARD_SIZE,
+        Color::Black => bitboard << BOARD_SIZE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POSITIONS: [&str; 4] = [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+        "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    ];
+
+    fn board(fen: &str) -> Board {
+        *GameState::from_fen(fen).unwrap().board()
+    }
+
+    /// Swap the colors of a position, turning the board upside down
+    fn mirror(fen: &str) -> String {
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+        let swap_case = |text: &str| -> String {
+            text.chars()
+                .map(|c| match c.is_ascii_uppercase() {
+                    true => c.to_ascii_lowercase(),
+                    false => c.to_ascii_uppercase(),
+                })
+                .collect()
+        };
+        let placement: Vec<String> = fields[0].split('/').rev().map(swap_case).collect();
+        let side = match fields[1] {
+            "w" => "b",
+            _ => "w",
+        };
+
+        format!(
+            "{} {side} {} - {} {}",
+            placement.join("/"),
+            swap_case(fields[2]),
+            fields[4],
+            fields[5]
+        )
+    }
+
+    #[test]
+    fn test_starting_position_is_balanced() {
+        let evaluation = evaluate_terms(&GameState::starting_position());
+
+        assert_eq!(
+            evaluation,
+            Evaluation {
+                phase: MAX_PHASE,
+                ..Default::default()
+            }
+        );
+        assert_eq!(evaluation.total(), 0);
+    }
+
+    #[test]
+    fn test_mirrored_positions_score_the_same() {
+        for fen in POSITIONS {
+            let evaluation = evaluate_terms(&GameState::from_fen(fen).unwrap());
+            let mirrored = evaluate_terms(&GameState::from_fen(&mirror(fen)).unwrap());
+
+            assert_eq!(evaluation, mirrored, "{fen}");
+        }
+    }
+
+    #[test]
+    fn test_side_to_move_point_of_view() {
+        let white_up_a_rook = "4k3/8/8/8/8/8/8/R3K3";
+
+        let white_to_move =
+            evaluate(&GameState::from_fen(&format!("{white_up_a_rook} w - - 0 1")).unwrap());
+        let black_to_move =
+            evaluate(&GameState::from_fen(&format!("{white_up_a_rook} b - - 0 1")).unwrap());
+
+        assert!(white_to_move > ROOK_VALUE.eg - 100);
+        assert_eq!(black_to_move, -white_to_move);
+    }
+
+    #[test]
+    fn test_phase() {
+        assert_eq!(
+            phase(&board("4k3/pppppppp/8/8/8/8/PPPPPPPP/4K3 w - - 0 1")),
+            0
+        );
+        assert_eq!(phase(&board("3qk3/8/8/8/8/8/8/R3K3 w - - 0 1")), 6);
+    }
+
+    #[test]
+    fn test_doubled_and_isolated_pawns() {
+        // The e-pawns are doubled and isolated, and the Black pawn on d7 stops them from passing
+        let board = board("4k3/3p4/8/8/8/4P3/4P3/4K3 w - - 0 1");
+
+        assert_eq!(
+            pawn_structure(&board, Color::White),
+            DOUBLED_PAWN + ISOLATED_PAWN * 2
+        );
+        assert_eq!(pawn_structure(&board, Color::Black), ISOLATED_PAWN);
+    }
+
+    #[test]
+    fn test_passed_pawns() {
+        let board = board("4k3/8/8/3P4/8/8/p7/4K3 w - - 0 1");
+
+        assert_eq!(
+            pawn_structure(&board, Color::White),
+            ISOLATED_PAWN + PASSED_PAWN[3]
+        );
+        assert_eq!(
+            pawn_structure(&board, Color::Black),
+            ISOLATED_PAWN + PASSED_PAWN[5]
+        );
+    }
+
+    #[test]
+    fn test_mobility() {
+        let board = board("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+
+        assert_eq!(mobility(&board, Color::White), KNIGHT_MOBILITY * 4);
+
+        let board = self::board("4k3/8/8/8/3N4/8/8/R3K3 w - - 0 1");
+
+        assert_eq!(
+            mobility(&board, Color::White),
+            KNIGHT_MOBILITY * 8 + ROOK_MOBILITY * 10
+        );
+    }
+
+    #[test]
+    fn test_king_safety() {
+        let castled = board("6k1/8/8/8/8/8/5PPP/6K1 w - - 0 1");
+        // The Rook on the second rank sweeps f2, g2 and h2
+        let exposed = board("6k1/8/8/8/8/8/r7/6K1 w - - 0 1");
+
+        assert_eq!(king_safety(&castled, Color::White), PAWN_SHIELD * 3);
+        assert_eq!(king_safety(&exposed, Color::White), KING_ZONE_ATTACK * 3);
+    }
+
+    #[test]
+    fn test_display_lists_every_term() {
+        let text = evaluate_terms(&GameState::starting_position()).to_string();
+
+        for term in [
+            "Material",
+            "Piece-squares",
+            "Pawn structure",
+            "Mobility",
+            "King safety",
+            "Total",
+        ] {
+            assert!(text.contains(term), "{term}");
+        }
+    }
+}
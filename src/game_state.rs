@@ -1,13 +1,42 @@
+mod draw_rules;
+mod fen;
+mod outcome;
+mod perft;
+mod pgn_export;
+mod resolve;
+mod san;
+mod zobrist;
+
 use crate::Board;
-use crate::errors::MoveError;
-use crate::errors::constants::{INTERNAL_ERROR_01, INTERNAL_ERROR_02};
-use crate::io::file_manager::initial_positions;
-use crate::movement::{ChessPosition, Move, Position};
-use crate::pgn::pgn_parser::parse_move;
-use crate::pieces::types::{King, Rook};
+use crate::errors::constants::{INTERNAL_ERROR_01, INTERNAL_ERROR_07, INTERNAL_ERROR_08};
+use crate::errors::{MoveError, SetupError, SetupErrorKind};
+use crate::io::file_manager::open_file;
+use crate::io::positions::{DEFAULT_POSITIONS, read_positions};
+use crate::movement::{ChessPosition, Move, MoveNotation, Position, San};
+use crate::pieces::types::{King, Pawn, Rook};
 use crate::pieces::{Color, Piece, PieceType};
-use crate::utils::helper_functions::get_next_char;
+pub use outcome::GameOutcome;
+use std::io::BufRead;
 use std::mem::{discriminant, swap};
+use std::path::Path;
+
+#[derive(Clone, PartialEq, Debug)]
+/// A move played through [`GameState::handle_move`], along with its notation in SAN
+pub struct PlayedMove {
+    pub move_: Move,
+    pub san: String,
+    undo_info: UndoInfo,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+/// State overwritten by a move, which cannot be recomputed when taking it back
+struct UndoInfo {
+    moved_piece: Piece,
+    additional_piece: Option<Piece>,
+    captured_piece: Option<Piece>,
+    en_passant_target: Option<Position>,
+    halfmove_clock: usize,
+}
 
 #[derive(Clone, PartialEq, Debug)]
 pub struct GameState {
@@ -17,6 +46,21 @@ pub struct GameState {
     turn: Color,
     white_king_position: Position,
     black_king_position: Position,
+    en_passant_target: Option<Position>,
+    halfmove_clock: usize,
+    fullmove_number: usize,
+    /// FEN of the position the game started from
+    initial_fen: String,
+    history: Vec<PlayedMove>,
+    /// Moves taken back with [`GameState::undo`], the most recent last
+    undone: Vec<PlayedMove>,
+    /// Zobrist key of the current position, see [`GameState::zobrist_key`]
+    key: u64,
+    /// Zobrist key of every position since the start of the game, the current one last
+    positions: Vec<u64>,
+    /// Outcome declared by a player rather than reached on the board: a claimed draw, a
+    /// resignation or a timeout
+    declared_outcome: Option<GameOutcome>,
     initialized: bool,
 }
 
@@ -35,6 +79,15 @@ impl GameState {
             turn: Color::White,
             white_king_position: Default::default(),
             black_king_position: Default::default(),
+            en_passant_target: None,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            initial_fen: String::new(),
+            history: Vec::new(),
+            undone: Vec::new(),
+            key: 0,
+            positions: Vec::new(),
+            declared_outcome: None,
             initialized: Default::default(),
         }
     }
@@ -63,13 +116,53 @@ impl GameState {
         self.board.get_piece(position)
     }
 
-    /// Given the piece type, the destination square, the disambiguation character and whether
+    /// Number of plies since the last capture or pawn move
+    pub fn halfmove_clock(&self) -> usize {
+        self.halfmove_clock
+    }
+
+    /// Number of the current full move, starting at 1 and incremented after each Black move
+    pub fn fullmove_number(&self) -> usize {
+        self.fullmove_number
+    }
+
+    /// The square skipped over by a pawn's two-square advance on the previous ply, if any
+    pub fn en_passant_target(&self) -> Option<Position> {
+        self.en_passant_target
+    }
+
+    /// FEN of the position the game started from
+    pub fn initial_fen(&self) -> &str {
+        &self.initial_fen
+    }
+
+    /// Moves played so far, in order
+    pub fn history(&self) -> &[PlayedMove] {
+        &self.history
+    }
+
+    /// Build the move of the piece on `origin` to `destination`, flagging en passant captures
+    pub fn build_move(&self, origin: Position, destination: Position) -> Move {
+        let is_pawn = self
+            .board
+            .get_piece(origin)
+            .is_some_and(|piece| matches!(piece.piece_type, PieceType::Pawn(_)));
+
+        if is_pawn && origin.col != destination.col && self.en_passant_target == Some(destination) {
+            Move::with_en_passant(origin, destination)
+        }
+        else {
+            Move::new(origin, destination)
+        }
+    }
+
+    /// Given the piece type, the destination square, the disambiguation characters and whether
     /// it's a capture, find the piece that best matches these parameters and return its position
     pub fn find_piece_position(
         &self,
         piece_type: PieceType,
         destination: Position,
-        disambiguation: Option<char>,
+        disambiguation: &[char],
         capture: bool,
     ) -> Result<Position, MoveError> {
         let mut matching_positions = Vec::new();
@@ -83,14 +176,15 @@ impl GameState {
             return Err(MoveError::NoPieceAvailable);
         }
         if matching_positions.len() > 1 {
-            let Some(disambiguation) = disambiguation
-            else {
+            if disambiguation.is_empty() {
                 return Err(MoveError::MoreThanOnePieceAvailable);
-            };
+            }
 
             matching_positions.retain(|pos| -> bool {
                 let chess_pos: ChessPosition = (*pos).try_into().expect(INTERNAL_ERROR_01);
-                disambiguation == chess_pos.line || disambiguation == chess_pos.col
+                disambiguation
+                    .iter()
+                    .all(|c| *c == chess_pos.line || *c == chess_pos.col)
             });
 
             if matching_positions.len() != 1 {
@@ -117,6 +211,12 @@ impl GameState {
         }
 
         if capture {
+            if let PieceType::Pawn(_) = piece.piece_type
+                && self.en_passant_target == Some(destination)
+            {
+                return Ok(Pawn::attacks(piece.color, origin, destination));
+            }
+
             piece.attacks(&self.board, origin, destination, true, true)
         }
         else {
@@ -124,12 +224,60 @@ impl GameState {
         }
     }
 
+    /// Whether `color` may still castle on the given side: neither its King nor the Rook involved
+    /// have moved. Squares being attacked or occupied are not taken into account.
+    pub fn has_castling_rights(&self, color: Color, is_short_castle: bool) -> bool {
+        let (king_source, _) = King::get_castle_move(color, is_short_castle);
+        let (rook_source, _) = Rook::get_castle_move(color, is_short_castle);
+
+        let has_rights = |position: Position, piece_type: PieceType| {
+            self.board.get_piece(position).is_some_and(|piece| {
+                piece.color == color
+                    && discriminant(&piece.piece_type) == discriminant(&piece_type)
+                    && match is_short_castle {
+                        true => piece.is_short_castling_available(),
+                        false => piece.is_long_castling_available(),
+                    }
+            })
+        };
+
+        has_rights(king_source, PieceType::King(Default::default()))
+            && has_rights(rook_source, PieceType::Rook(Default::default()))
+    }
+
+    /// Check that a promotion piece is given if, and only if, a pawn reaches the last rank
+    pub fn validate_promotion(&self, next_move: Move) -> Result<(), MoveError> {
+        let reaches_last_rank = self
+            .board
+            .get_piece(next_move.source())
+            .is_some_and(|piece| {
+                matches!(piece.piece_type, PieceType::Pawn(_))
+                    && Pawn::is_last_rank(piece.color, next_move.destination())
+            });
+
+        match (reaches_last_rank, next_move.promotion) {
+            (false, None) => Ok(()),
+            (true, None) => Err(MoveError::MissingPromotion),
+            (false, Some(_)) => Err(MoveError::InvalidPromotion(
+                "Only a pawn reaching the last rank can be promoted",
+            )),
+            (
+                true,
+                Some(PieceType::Queen | PieceType::Rook(_) | PieceType::Bishop | PieceType::Knight),
+            ) => Ok(()),
+            (true, Some(_)) => Err(MoveError::InvalidPromotion(
+                "A pawn can only be promoted to a Queen, Rook, Bishop or Knight",
+            )),
+        }
+    }
+
     /// Find and return the King and Rook moves (in that order) needed for castling
     pub fn find_castling_move(&self, is_short_castle: bool) -> Result<Move, MoveError> {
         let (king_source, king_destination) = King::get_castle_move(self.turn, is_short_castle);
         let king = self
             .board
             .get_piece(king_source)
+            .filter(|piece| piece.color == self.turn)
             .ok_or(MoveError::InvalidCastle(
                 "The King is no longer on its original square",
             ))?;
@@ -138,6 +286,7 @@ impl GameState {
         let rook = self
             .board
             .get_piece(rook_source)
+            .filter(|piece| piece.color == self.turn)
             .ok_or(MoveError::InvalidCastle(
                 "The Rook is no longer on its original square",
             ))?;
@@ -157,45 +306,231 @@ impl GameState {
     }
 
     pub fn handle_move(&mut self, str_move: &str) -> Result<(), MoveError> {
+        self.handle_move_as(str_move, &San)
+    }
+
+    /// Play a move of the side to move written in the given notation, e.g.,
+    /// [`Coordinate`](crate::movement::Coordinate) for `e2e4`
+    pub fn handle_move_as(
+        &mut self,
+        str_move: &str,
+        notation: &dyn MoveNotation,
+    ) -> Result<(), MoveError> {
         if !self.initialized {
             panic!("Should call 'initialize' before 'handle_move'");
         }
+        if self.is_over() {
+            return Err(MoveError::GameOver);
+        }
+
+        let next_move = notation.parse_move(self, str_move)?;
+
+        self.validate_move(next_move)?;
+        self.record_move(next_move);
+
+        Ok(())
+    }
+
+    /// Play a move of the side to move given as a [`Move`] rather than in SAN, e.g., one from
+    /// [`GameState::legal_moves`] or a [`MoveNotation`]
+    pub fn play_move(&mut self, next_move: Move) -> Result<(), MoveError> {
+        if self.is_over() {
+            return Err(MoveError::GameOver);
+        }
+
+        let unannotated = Move {
+            annotation: None,
+            ..next_move
+        };
+        if !self.legal_moves().contains(&unannotated) {
+            self.validate_move(next_move)?;
+            return Err(MoveError::InvalidMove("The piece cannot go to this square"));
+        }
+
+        self.record_move(next_move);
+
+        Ok(())
+    }
+
+    /// Play a validated move, recording it in SAN, and forget the moves that could be redone
+    fn record_move(&mut self, next_move: Move) {
+        let mut san = self.to_san(next_move);
+        if let Some(annotation) = next_move.annotation {
+            san.push_str(&annotation.to_string());
+        }
+
+        self.play(next_move, san);
+        self.undone.clear();
+    }
+
+    /// Take back the last move, restoring the previous state exactly. Return the move taken back,
+    /// or `None` if no move has been played.
+    pub fn undo(&mut self) -> Option<Move> {
+        let played_move = self.history.pop()?;
+        let (last_move, undo_info) = (played_move.move_, &played_move.undo_info);
+        let (board_key, rights_key) = (self.board.key(), self.rights_key());
+
+        self.declared_outcome = None;
+        self.positions.pop();
+
+        self.turn.flip();
+
+        self.board.undo_move(last_move, undo_info.captured_piece);
+        self.board
+            .add_piece(undo_info.moved_piece, last_move.source());
+        if let Some(additional_move) = last_move.additional
+            && let Some(additional_piece) = undo_info.additional_piece
+        {
+            self.board
+                .add_piece(additional_piece, additional_move.source);
+        }
 
-        let next_move = parse_move(self, str_move)?;
+        if let Some(captured_piece) = undo_info.captured_piece {
+            match captured_piece.color {
+                Color::White => self.captured_white_pieces.pop(),
+                Color::Black => self.captured_black_pieces.pop(),
+            };
+        }
+
+        if let PieceType::King(_) = undo_info.moved_piece.piece_type {
+            match self.turn {
+                Color::White => self.white_king_position = last_move.source(),
+                Color::Black => self.black_king_position = last_move.source(),
+            }
+        }
+
+        self.en_passant_target = undo_info.en_passant_target;
+        self.halfmove_clock = undo_info.halfmove_clock;
+        if self.turn == Color::Black {
+            self.fullmove_number -= 1;
+        }
+        self.update_key(board_key, rights_key);
+
+        self.undone.push(played_move);
+
+        Some(last_move)
+    }
 
+    /// Play again the last move taken back with [`GameState::undo`]. Return the move played, or
+    /// `None` if there is nothing to redo. Playing a new move discards the moves left to redo.
+    pub fn redo(&mut self) -> Option<Move> {
+        let played_move = self.undone.pop()?;
+
+        self.play(played_move.move_, played_move.san);
+
+        Some(played_move.move_)
+    }
+
+    /// Play a legal move without writing its notation, to be taken back with
+    /// [`GameState::unmake_move`] while searching
+    pub(crate) fn make_move(&mut self, next_move: Move) {
+        self.play(next_move, String::new());
+    }
+
+    /// Take back the last move played with [`GameState::make_move`]
+    pub(crate) fn unmake_move(&mut self) {
+        self.undo();
+        self.undone.pop();
+    }
+
+    /// Apply an already validated move and record it in the history
+    fn play(&mut self, next_move: Move, san: String) {
+        let undo_info = UndoInfo {
+            moved_piece: self
+                .board
+                .get_piece(next_move.source())
+                .expect(INTERNAL_ERROR_07),
+            additional_piece: next_move
+                .additional
+                .and_then(|additional_move| self.board.get_piece(additional_move.source)),
+            captured_piece: self.board.get_piece(next_move.capture_position()),
+            en_passant_target: self.en_passant_target,
+            halfmove_clock: self.halfmove_clock,
+        };
+
+        self.apply_move(next_move);
+        self.positions.push(self.zobrist_key());
+        self.history.push(PlayedMove {
+            move_: next_move,
+            san,
+            undo_info,
+        });
+    }
+
+    /// Check that `next_move` gives check or, if `checkmate` is set, that it checkmates
+    pub fn validate_check(&self, next_move: Move, checkmate: bool) -> Result<(), MoveError> {
+        let mut game_state = self.clone();
+        game_state.validate_move(next_move)?;
+        game_state.apply_move(next_move);
+
+        if checkmate && !game_state.verify_checkmate() {
+            return Err(MoveError::InvalidCheck("This move does not checkmate"));
+        }
+        if !game_state.is_check() {
+            return Err(MoveError::InvalidCheck("This move does not give check"));
+        }
+
+        Ok(())
+    }
+
+    /// Whether the King of the side to move is in check
+    pub fn is_check(&self) -> bool {
+        let king_pos = match self.turn {
+            Color::White => self.white_king_position,
+            Color::Black => self.black_king_position,
+        };
+
+        self.is_king_in_check(king_pos, self.turn)
+    }
+
+    /// Ensure a parsed move does not leave (or, when castling, take) the King through check
+    fn validate_move(&self, next_move: Move) -> Result<(), MoveError> {
         if next_move.is_castling() {
             self.validate_castling_path(next_move)?;
         }
 
-        self.verify_king_in_check(next_move)?;
+        self.verify_king_in_check(next_move)
+    }
+
+    /// Update the game state with an already validated move
+    fn apply_move(&mut self, next_move: Move) {
+        let (board_key, rights_key) = (self.board.key(), self.rights_key());
 
         self.update_king_position(next_move);
 
+        self.update_clocks(next_move);
+
         self.board.update_piece_state(next_move.source());
         if let Some(additional_move) = next_move.additional {
             self.board.update_piece_state(additional_move.source);
         }
 
-        self.update_captured_pieces_list(next_move.destination());
+        self.update_captured_pieces_list(next_move.capture_position());
+
+        self.update_en_passant_target(next_move);
 
         self.board.perform_move(next_move);
 
         self.turn.flip();
-
-        Ok(())
+        self.update_key(board_key, rights_key);
     }
 
-    fn verify_king_in_check(&mut self, next_move: Move) -> Result<(), MoveError> {
-        let captured_piece = self.board.get_piece(next_move.destination());
+    /// Play the move on a copy of the board, and check whether the King is then attacked
+    fn verify_king_in_check(&self, next_move: Move) -> Result<(), MoveError> {
+        let mut board = self.board;
+        board.perform_move(next_move);
 
-        self.board.perform_move(next_move);
-
-        let king_pos = self.get_king_pos(next_move.destination());
-        let king_in_check = self.is_king_in_check(king_pos, self.turn);
-
-        self.board.undo_move(next_move, captured_piece);
+        let king_pos = match board.get_piece(next_move.destination()) {
+            Some(piece) if matches!(piece.piece_type, PieceType::King(_)) => {
+                next_move.destination()
+            }
+            _ => match self.turn {
+                Color::White => self.white_king_position,
+                Color::Black => self.black_king_position,
+            },
+        };
 
-        if king_in_check {
+        if board.is_square_attacked(king_pos, self.turn.opposite()) {
             return Err(MoveError::KingWouldBeInCheck);
         }
 
@@ -212,6 +547,55 @@ impl GameState {
         }
     }
 
+    fn update_clocks(&mut self, next_move: Move) {
+        let source_piece = self.board.get_piece(next_move.source()).unwrap();
+        let is_capture = self
+            .board
+            .is_position_occupied(next_move.capture_position());
+
+        if is_capture || matches!(source_piece.piece_type, PieceType::Pawn(_)) {
+            self.halfmove_clock = 0;
+        }
+        else {
+            self.halfmove_clock += 1;
+        }
+
+        if self.turn == Color::Black {
+            self.fullmove_number += 1;
+        }
+    }
+
+    fn update_en_passant_target(&mut self, next_move: Move) {
+        let source_piece = self.board.get_piece(next_move.source()).unwrap();
+        let (source, destination) = (next_move.source(), next_move.destination());
+
+        self.en_passant_target = match source_piece.piece_type {
+            PieceType::Pawn(_) if source.line.abs_diff(destination.line) == 2 => Some(
+                Position::new((source.line + destination.line) / 2, source.col),
+            ),
+            _ => None,
+        };
+    }
+
+    /// Return every en passant capture available to the side to move, without checking whether
+    /// it would leave the King in check
+    fn en_passant_moves(&self) -> Vec<Move> {
+        let Some(target) = self.en_passant_target
+        else {
+            return Vec::new();
+        };
+
+        self.board
+            .into_iter()
+            .filter(|(piece, pos)| {
+                piece.color == self.turn
+                    && matches!(piece.piece_type, PieceType::Pawn(_))
+                    && Pawn::attacks(piece.color, *pos, target)
+            })
+            .map(|(_, pos)| Move::with_en_passant(pos, target))
+            .collect()
+    }
+
     fn update_king_position(&mut self, next_move: Move) {
         let source_piece = self.board.get_piece(next_move.source()).unwrap();
         if let PieceType::King(_) = source_piece.piece_type {
@@ -222,76 +606,109 @@ impl GameState {
         }
     }
 
-    fn get_king_pos(&self, destination: Position) -> Position {
-        match self.board.get_piece(destination).unwrap().piece_type {
-            PieceType::King(_) => destination,
-            _ => match self.turn {
-                Color::White => self.white_king_position,
-                Color::Black => self.black_king_position,
-            },
-        }
+    fn is_king_in_check(&self, king_pos: Position, color: Color) -> bool {
+        self.board.is_square_attacked(king_pos, color.opposite())
     }
 
-    fn is_king_in_check(&self, king_pos: Position, color: Color) -> bool {
-        for (piece, pos) in self
-            .board
-            .into_iter()
-            .filter(|(piece, _)| piece.color != color)
-        {
-            if piece
-                .attacks(&self.board, pos, king_pos, false, false)
-                .expect(INTERNAL_ERROR_02)
-            {
-                return true;
-            }
-        }
+    /// Set up the game from a positions file, or from the standard starting position when no file
+    /// is given
+    pub fn initialize(&mut self, positions_file: Option<&Path>) -> Result<(), SetupError> {
+        *self = match positions_file {
+            Some(path) => Self::from_positions_file(path)?,
+            None => Self::starting_position(),
+        };
 
-        false
+        Ok(())
     }
 
-    pub fn initialize(&mut self, positions_file: Option<&str>) {
-        for wrapped_line in initial_positions(positions_file) {
-            let line = wrapped_line.expect("Error reading file line");
-            let mut chars = line.chars();
+    /// Build a game state for the standard starting position, with White to move
+    pub fn starting_position() -> GameState {
+        Self::from_positions_str(DEFAULT_POSITIONS).expect(INTERNAL_ERROR_08)
+    }
 
-            let piece_color: Color = get_next_char(&line, &mut chars)
-                .try_into()
-                .unwrap_or_else(|_| panic!("Could not parse color character from line {line}"));
+    /// Build a game state, with White to move, from a file listing one piece per line (e.g.,
+    /// `WKe1`)
+    pub fn from_positions_file(path: impl AsRef<Path>) -> Result<GameState, SetupError> {
+        let path = path.as_ref();
 
-            let piece_type: PieceType = get_next_char(&line, &mut chars)
-                .try_into()
-                .unwrap_or_else(|_| panic!("Could not parse piece character from line {line}"));
+        open_file(path)
+            .map_err(SetupError::from)
+            .and_then(Self::from_positions_reader)
+            .map_err(|err| err.in_file(path))
+    }
 
-            let chess_col = get_next_char(&line, &mut chars);
+    /// Build a game state, with White to move, from a string listing one piece per line
+    pub fn from_positions_str(positions: &str) -> Result<GameState, SetupError> {
+        Self::from_positions_reader(positions.as_bytes())
+    }
 
-            let chess_line = get_next_char(&line, &mut chars);
+    /// Build a game state, with White to move, from a reader yielding one piece per line
+    pub fn from_positions_reader(reader: impl BufRead) -> Result<GameState, SetupError> {
+        Self::from_board(read_positions(reader)?, Color::White)
+    }
 
-            let piece_position = ChessPosition::new(chess_line, chess_col)
-                .try_into()
-                .unwrap_or_else(|_| {
-                    panic!("Could not convert ChessPosition {chess_col}{chess_line} to Position")
-                });
+    /// Build a game state from a board, with `turn` to move. Castling rights and two-square
+    /// pawn advances are taken from the pieces themselves.
+    pub fn from_board(board: Board, turn: Color) -> Result<GameState, SetupError> {
+        let mut game_state = GameState::new();
+        game_state.board = board;
+        game_state.turn = turn;
 
-            if let PieceType::King(_) = piece_type {
-                match piece_color {
-                    Color::White => self.white_king_position = piece_position,
-                    Color::Black => self.black_king_position = piece_position,
-                }
+        game_state
+            .validate_setup()
+            .map_err(SetupError::IllegalPosition)?;
+        game_state.finish_setup();
+
+        Ok(game_state)
+    }
+
+    /// Record the current position as the one the game starts from and allow moves to be played
+    fn finish_setup(&mut self) {
+        self.initial_fen = self.to_fen();
+        self.key = self.compute_full_key();
+        self.positions = vec![self.key];
+        self.initialized = true;
+    }
+
+    /// Check that the position can occur in a game, locating both kings along the way: each side
+    /// has exactly one king, no pawn stands on a back rank and the side not to move is not in
+    /// check
+    fn validate_setup(&mut self) -> Result<(), SetupErrorKind> {
+        for color in [Color::White, Color::Black] {
+            let mut kings = self.board.into_iter().filter(|(piece, _)| {
+                piece.color == color && matches!(piece.piece_type, PieceType::King(_))
+            });
+
+            let (_, king_position) = kings.next().ok_or(SetupErrorKind::MissingKing(color))?;
+            if kings.next().is_some() {
+                return Err(SetupErrorKind::TooManyKings(color));
             }
 
-            if self.board.is_position_occupied(piece_position) {
-                panic!(
-                    "Duplicate piece position in initial setup: {chess_col}{chess_line} (line: {line})"
-                );
+            match color {
+                Color::White => self.white_king_position = king_position,
+                Color::Black => self.black_king_position = king_position,
             }
+        }
 
-            self.board
-                .add_piece(Piece::new(piece_type, piece_color), piece_position);
+        if self.board.into_iter().any(|(piece, position)| {
+            matches!(piece.piece_type, PieceType::Pawn(_)) && Pawn::is_back_rank(position)
+        }) {
+            return Err(SetupErrorKind::PawnOnBackRank);
         }
-        self.initialized = true;
+
+        let opponent = self.turn.opposite();
+        let opponent_king_position = match opponent {
+            Color::White => self.white_king_position,
+            Color::Black => self.black_king_position,
+        };
+        if self.is_king_in_check(opponent_king_position, opponent) {
+            return Err(SetupErrorKind::OpponentInCheck(opponent));
+        }
+
+        Ok(())
     }
 
-    fn validate_castling_path(&mut self, mut next_move: Move) -> Result<(), MoveError> {
+    fn validate_castling_path(&self, mut next_move: Move) -> Result<(), MoveError> {
         next_move.additional = None;
 
         let (mut start, mut end) = (next_move.source().col, next_move.destination().col);
@@ -307,85 +724,78 @@ impl GameState {
         Ok(())
     }
 
-    pub fn verify_checkmate(&mut self) -> bool {
-        let (color, king_pos) = match self.turn {
-            Color::White => (Color::White, self.white_king_position),
-            Color::Black => (Color::Black, self.black_king_position),
-        };
+    /// Return every fully legal move available to the side to move, including captures, castling,
+    /// promotions and en passant captures
+    pub fn legal_moves(&self) -> Vec<Move> {
+        let mut candidates = Vec::new();
 
-        if !self.is_king_in_check(king_pos, color) {
-            return false;
-        }
-
-        // check if the King has a valid move to get out of check
-        let king = self.board.get_piece(king_pos).unwrap();
-        for dest in king.get_possible_moves(&self.board, king_pos) {
-            let next_move = Move::new(king_pos, dest);
-            let captured_piece = self.board.get_piece(next_move.destination());
-
-            self.board.perform_move(next_move);
-            let king_in_check = self.is_king_in_check(dest, color);
-            self.board.undo_move(next_move, captured_piece);
-
-            if !king_in_check {
-                return false;
-            }
-        }
-
-        // check if any other piece can cover the check
-        let pieces_to_check: Vec<(Piece, Position)> = self
+        for (piece, source) in self
             .board
             .into_iter()
-            .filter(|(piece, _)| {
-                piece.color == self.turn && !matches!(piece.piece_type, PieceType::King(_))
-            })
-            .collect();
-
-        for (piece, source) in pieces_to_check {
-            // get the piece possible movements
-            let possible_movements = piece.get_possible_moves(&self.board, source);
-
-            // check if any of them can cover the check
-            for dest in possible_movements {
-                let next_move = Move::new(source, dest);
-                let captured_piece = self.board.get_piece(next_move.destination());
-
-                self.board.perform_move(next_move);
-                let king_in_check = self.is_king_in_check(king_pos, color);
-                self.board.undo_move(next_move, captured_piece);
-
-                if !king_in_check {
-                    return false;
+            .filter(|(piece, _)| piece.color == self.turn)
+        {
+            let captures = Board::positions(
+                self.board.attacks_from(&piece, source) & self.board.pieces(self.turn.opposite()),
+            );
+
+            for destination in piece
+                .get_possible_moves(&self.board, source)
+                .into_iter()
+                .chain(captures)
+            {
+                let next_move = Move::new(source, destination);
+
+                if matches!(piece.piece_type, PieceType::Pawn(_))
+                    && Pawn::is_last_rank(piece.color, destination)
+                {
+                    for promotion in PieceType::promotion_types() {
+                        candidates.push(Move {
+                            promotion: Some(promotion),
+                            ..next_move
+                        });
+                    }
+                }
+                else {
+                    candidates.push(next_move);
                 }
             }
         }
 
-        true
+        candidates.extend(self.en_passant_moves());
+        candidates.extend(
+            [true, false]
+                .into_iter()
+                .filter_map(|is_short_castle| self.find_castling_move(is_short_castle).ok()),
+        );
+
+        candidates.retain(|next_move| self.validate_move(*next_move).is_ok());
+
+        candidates
     }
 
-    pub fn is_stalemate(&self) -> bool {
-        for (piece, pos) in self
-            .board
-            .into_iter()
-            .filter(|(piece_, _)| piece_.color == self.turn)
-        {
-            if !piece.get_possible_moves(self.board(), pos).is_empty() {
-                return false;
-            }
-        }
+    /// Whether the side to move is in check and has no legal move
+    pub fn verify_checkmate(&self) -> bool {
+        self.is_check() && self.legal_moves().is_empty()
+    }
 
-        true
+    /// Whether the side to move is not in check but has no legal move. Every candidate move is
+    /// played on the board, so pinned pieces and King moves into check do not count, while pawn
+    /// captures and en passant do
+    pub fn is_stalemate(&self) -> bool {
+        !self.is_check() && self.legal_moves().is_empty()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::utils::test_helper::setup_game_state;
+    use crate::utils::test_helper::{
+        fixture_path, random_playouts, setup_board, setup_game_state, setup_game_state_with_turn,
+    };
 
     #[test]
     fn test_validate_castling_path_short_castle_success() {
-        let mut game_state = setup_game_state(Some("tests/validate_castling_path_success.txt"));
+        let game_state = setup_game_state(Some("tests/validate_castling_path_success.txt"));
 
         let king_source = Position::new(7, 4);
         let king_destination = Position::new(7, 6);
@@ -400,7 +810,7 @@ mod tests {
 
     #[test]
     fn test_validate_castling_path_short_castle_fail() {
-        let mut game_state = setup_game_state(Some("tests/validate_castling_path_fail.txt"));
+        let game_state = setup_game_state(Some("tests/validate_castling_path_fail.txt"));
 
         let king_source = Position::new(7, 4);
         let king_destination = Position::new(7, 6);
@@ -416,7 +826,7 @@ mod tests {
 
     #[test]
     fn test_validate_castling_path_long_castle_success() {
-        let mut game_state = setup_game_state(Some("tests/validate_castling_path_success.txt"));
+        let game_state = setup_game_state(Some("tests/validate_castling_path_success.txt"));
 
         let king_source = Position::new(7, 4);
         let king_destination = Position::new(7, 2);
@@ -431,7 +841,7 @@ mod tests {
 
     #[test]
     fn test_validate_castling_path_long_castle_fail() {
-        let mut game_state = setup_game_state(Some("tests/validate_castling_path_fail.txt"));
+        let game_state = setup_game_state(Some("tests/validate_castling_path_fail.txt"));
 
         let king_source = Position::new(7, 4);
         let king_destination = Position::new(7, 2);
@@ -447,7 +857,7 @@ mod tests {
 
     #[test]
     fn test_stalemate() {
-        let mut game_state = setup_game_state(Some("tests/validate_stalemate.txt"));
+        let game_state = setup_game_state(Some("tests/validate_stalemate.txt"));
 
         assert!(game_state.is_stalemate());
         assert!(!game_state.verify_checkmate());
@@ -460,6 +870,24 @@ mod tests {
         assert!(!game_state.is_stalemate());
     }
 
+    #[test]
+    fn test_stalemate_with_pinned_piece() {
+        let game_state = setup_game_state(Some("tests/stalemate_pinned_piece.txt"));
+
+        assert!(game_state.is_stalemate());
+    }
+
+    #[test]
+    fn test_not_stalemate_when_only_move_is_capture() {
+        let game_state = setup_game_state(Some("tests/not_stalemate_capture_only.txt"));
+
+        assert!(!game_state.is_stalemate());
+        assert_eq!(
+            game_state.legal_moves(),
+            vec![Move::new(Position::new(4, 4), Position::new(3, 3))]
+        );
+    }
+
     #[test]
     fn test_undo_move_integrity() {
         let mut game_state = setup_game_state(None);
@@ -476,35 +904,245 @@ mod tests {
 
     #[test]
     fn test_checkmate_scholars_mate() {
-        let mut game_state = setup_game_state(Some("tests/scholars_mate.txt"));
-
-        game_state.turn.flip();
+        let game_state = setup_game_state_with_turn(Some("tests/scholars_mate.txt"), Color::Black);
 
         assert!(game_state.verify_checkmate());
     }
 
     #[test]
     fn test_checkmate_blocked_by_piece() {
-        let mut game_state = setup_game_state(Some("tests/check_can_be_blocked.txt"));
-
-        game_state.turn.flip();
+        let game_state =
+            setup_game_state_with_turn(Some("tests/check_can_be_blocked.txt"), Color::Black);
 
         assert!(!game_state.verify_checkmate());
     }
 
     #[test]
     fn test_checkmate_king_escapes() {
-        let mut game_state = setup_game_state(Some("tests/king_can_escape.txt"));
+        let game_state =
+            setup_game_state_with_turn(Some("tests/king_can_escape.txt"), Color::Black);
+
+        assert!(!game_state.verify_checkmate());
+    }
+
+    #[test]
+    fn test_en_passant_target_set_after_two_square_advance() -> Result<(), MoveError> {
+        let mut game_state = setup_game_state(None);
+
+        game_state.handle_move("e4")?;
+        assert_eq!(game_state.en_passant_target(), Some(Position::new(5, 4)));
+
+        game_state.handle_move("Nf6")?;
+        assert_eq!(game_state.en_passant_target(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_en_passant_capture() -> Result<(), MoveError> {
+        let mut game_state = setup_game_state(None);
+        game_state.handle_move("e4")?;
+        game_state.handle_move("a6")?;
+        game_state.handle_move("e5")?;
+        game_state.handle_move("d5")?;
+
+        let next_move = San.parse_move(&game_state, "exd6")?;
+        assert!(next_move.is_en_passant());
+        assert_eq!(next_move.capture_position(), Position::new(3, 3));
+
+        game_state.handle_move("exd6")?;
+
+        assert!(game_state.get_piece(Position::new(3, 3)).is_none());
+        assert!(game_state.get_piece(Position::new(3, 4)).is_none());
+        assert_eq!(
+            game_state.get_piece(Position::new(2, 3)).unwrap().color,
+            Color::White
+        );
+        assert_eq!(game_state.captured_black_pieces().len(), 1);
+        assert_eq!(game_state.en_passant_target(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_en_passant_expires_after_one_ply() -> Result<(), MoveError> {
+        let mut game_state = setup_game_state(None);
+        game_state.handle_move("e4")?;
+        game_state.handle_move("a6")?;
+        game_state.handle_move("e5")?;
+        game_state.handle_move("d5")?;
+        game_state.handle_move("a3")?;
+        game_state.handle_move("a5")?;
+
+        let result = game_state.handle_move("exd6");
+
+        assert_eq!(
+            result.unwrap_err(),
+            MoveError::InvalidCapture("Destination square is empty")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_en_passant_prevents_stalemate() -> Result<(), MoveError> {
+        let mut game_state = setup_game_state(Some("tests/en_passant_only_move.txt"));
+        assert!(game_state.is_stalemate());
+
+        game_state.turn.flip();
+        game_state.handle_move("e5")?;
+
+        assert!(!game_state.is_stalemate());
+
+        game_state.handle_move("dxe6")?;
+
+        assert_eq!(game_state.captured_black_pieces().len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_en_passant_escapes_checkmate() -> Result<(), MoveError> {
+        let mut game_state = setup_game_state(Some("tests/en_passant_escapes_checkmate.txt"));
 
         game_state.turn.flip();
+        game_state.handle_move("g5")?;
 
         assert!(!game_state.verify_checkmate());
+
+        game_state.handle_move("hxg6")?;
+
+        assert!(game_state.get_piece(Position::new(3, 6)).is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_legal_moves_initial_position() {
+        let game_state = setup_game_state(None);
+
+        let legal_moves = game_state.legal_moves();
+
+        assert_eq!(legal_moves.len(), 20);
+        assert!(legal_moves.contains(&Move::new(Position::new(6, 4), Position::new(4, 4))));
+        assert!(legal_moves.contains(&Move::new(Position::new(7, 6), Position::new(5, 5))));
+    }
+
+    #[test]
+    fn test_legal_moves_include_castling() {
+        let game_state = setup_game_state(Some("tests/validate_castling_path_success.txt"));
+
+        let castling_moves: Vec<Move> = game_state
+            .legal_moves()
+            .into_iter()
+            .filter(Move::is_castling)
+            .collect();
+
+        assert_eq!(castling_moves.len(), 2);
+    }
+
+    #[test]
+    fn test_legal_moves_exclude_castling_through_check() {
+        let game_state = setup_game_state(Some("tests/validate_castling_path_fail.txt"));
+
+        assert!(!game_state.legal_moves().iter().any(Move::is_castling));
+    }
+
+    #[test]
+    fn test_legal_moves_exclude_pinned_piece_moves() {
+        let game_state = setup_game_state(Some("tests/pinned_piece_exposes_king.txt"));
+        let rook_source = Position::new(6, 4);
+
+        let legal_moves = game_state.legal_moves();
+
+        assert_eq!(legal_moves.len(), 10);
+        assert!(
+            legal_moves
+                .iter()
+                .filter(|m| m.source() == rook_source)
+                .all(|m| m.destination().col == rook_source.col)
+        );
     }
 
     #[test]
-    #[should_panic(expected = "Duplicate piece position in initial setup")]
-    fn test_initialize_panics_on_duplicate_square() {
-        let _ = setup_game_state(Some("tests/duplicate_square.txt"));
+    fn test_legal_moves_include_promotions() {
+        let game_state = setup_game_state(Some("tests/promotion.txt"));
+
+        let promotions: Vec<Move> = game_state
+            .legal_moves()
+            .into_iter()
+            .filter(Move::is_promotion)
+            .collect();
+
+        assert_eq!(promotions.len(), 8);
+    }
+
+    #[test]
+    fn test_legal_moves_include_en_passant() -> Result<(), MoveError> {
+        let mut game_state = setup_game_state(None);
+        game_state.handle_move("e4")?;
+        game_state.handle_move("a6")?;
+        game_state.handle_move("e5")?;
+        game_state.handle_move("d5")?;
+
+        let en_passant = Move::with_en_passant(Position::new(3, 4), Position::new(2, 3));
+
+        assert!(game_state.legal_moves().contains(&en_passant));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_initialize_rejects_duplicate_square() {
+        let mut game_state = GameState::new();
+
+        assert_eq!(
+            game_state.initialize(Some(&fixture_path("tests/setup/duplicate_square.txt"))),
+            Err(SetupError::InvalidLine(2, SetupErrorKind::DuplicateSquare))
+        );
+        assert_eq!(game_state, GameState::new());
+    }
+
+    #[test]
+    fn test_initialize_rejects_illegal_positions() {
+        let cases = [
+            (
+                "tests/setup/missing_king.txt",
+                SetupErrorKind::MissingKing(Color::Black),
+            ),
+            (
+                "tests/setup/too_many_kings.txt",
+                SetupErrorKind::TooManyKings(Color::White),
+            ),
+            (
+                "tests/scholars_mate.txt",
+                SetupErrorKind::OpponentInCheck(Color::Black),
+            ),
+        ];
+
+        for (positions_file, kind) in cases {
+            let mut game_state = GameState::new();
+
+            assert_eq!(
+                game_state.initialize(Some(&fixture_path(positions_file))),
+                Err(SetupError::IllegalPosition(kind)),
+                "{positions_file}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_board_rejects_pawn_on_back_rank() {
+        let mut board = setup_board(Some("tests/promotion.txt"));
+        board.add_piece(
+            Piece::new(PieceType::Pawn(Pawn::new()), Color::Black),
+            Position::new(7, 0),
+        );
+
+        assert_eq!(
+            GameState::from_board(board, Color::White),
+            Err(SetupError::IllegalPosition(SetupErrorKind::PawnOnBackRank))
+        );
     }
 
     #[test]
@@ -532,4 +1170,84 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_undo_and_redo_restore_every_state() {
+        let mut snapshots = Vec::new();
+        let final_states =
+            random_playouts(0x9e37_79b9_7f4a_7c15, 4, 60, |game_state, next_move| {
+                snapshots.push((game_state.clone(), next_move));
+            });
+
+        // The snapshots of the last game are on top of the stack
+        for final_state in final_states.into_iter().rev() {
+            let mut game_state = final_state.clone();
+
+            while let Some(played_move) = game_state.undo() {
+                let (snapshot, next_move) = snapshots.pop().unwrap();
+                assert_eq!(played_move, next_move);
+
+                let mut restored = game_state.clone();
+                restored.undone.clear();
+                assert_eq!(restored, snapshot, "{}", snapshot.to_fen());
+            }
+
+            while game_state.redo().is_some() {}
+            assert_eq!(game_state, final_state);
+        }
+        assert!(snapshots.is_empty());
+    }
+
+    #[test]
+    fn test_undo_castling_restores_rights() -> Result<(), MoveError> {
+        let mut game_state = GameState::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 3 10").unwrap();
+        let initial_state = game_state.clone();
+
+        game_state.handle_move("O-O")?;
+        game_state.handle_move("O-O-O")?;
+
+        assert_eq!(game_state.to_fen(), "2kr3r/8/8/8/8/8/8/R4RK1 w - - 5 11");
+
+        game_state.undo();
+        game_state.undo();
+        game_state.undone.clear();
+
+        assert_eq!(game_state, initial_state);
+        assert_eq!(game_state.to_fen(), "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 3 10");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_undo_capture_and_promotion() -> Result<(), MoveError> {
+        let mut game_state = GameState::from_fen("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        game_state.handle_move("axb8=Q+")?;
+        let promotion = game_state.history()[0].move_;
+
+        assert_eq!(game_state.captured_black_pieces().len(), 1);
+        assert_eq!(game_state.undo(), Some(promotion));
+        assert!(game_state.captured_black_pieces().is_empty());
+        assert_eq!(game_state.to_fen(), "1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_move_discards_redo() -> Result<(), MoveError> {
+        let mut game_state = GameState::starting_position();
+
+        assert_eq!(game_state.undo(), None);
+        assert_eq!(game_state.redo(), None);
+
+        game_state.handle_move("e4")?;
+        game_state.undo();
+        game_state.handle_move("d4")?;
+
+        assert_eq!(game_state.redo(), None);
+        assert_eq!(game_state.history().len(), 1);
+        assert_eq!(game_state.history()[0].san, "d4");
+
+        Ok(())
+    }
 }
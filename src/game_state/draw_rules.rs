@@ -0,0 +1,292 @@
+use super::{GameOutcome, GameState};
+use crate::pieces::PieceType;
+
+/// Halfmoves without capture or pawn move after which either player may claim a draw
+const FIFTY_MOVE_RULE_HALFMOVES: usize = 100;
+/// Halfmoves without capture or pawn move after which the game is drawn automatically
+const SEVENTY_FIVE_MOVE_RULE_HALFMOVES: usize = 150;
+/// Occurrences of a position allowing either player to claim a draw
+const THREEFOLD_REPETITION: usize = 3;
+/// Occurrences of a position drawing the game automatically
+const FIVEFOLD_REPETITION: usize = 5;
+
+impl GameState {
+    /// Whether the last 50 moves of each player were played without any capture or pawn move, so
+    /// that the side to move may claim a draw
+    pub fn can_claim_fifty_move_draw(&self) -> bool {
+        self.halfmove_clock >= FIFTY_MOVE_RULE_HALFMOVES
+    }
+
+    /// Whether the last 75 moves of each player were played without any capture or pawn move, which
+    /// ends the game in a draw unless the last move delivered checkmate
+    pub fn is_seventy_five_move_draw(&self) -> bool {
+        self.halfmove_clock >= SEVENTY_FIVE_MOVE_RULE_HALFMOVES && !self.verify_checkmate()
+    }
+
+    /// Number of times the current position occurred in the game, counting the current one
+    pub fn repetition_count(&self) -> usize {
+        let Some(current) = self.positions.last()
+        else {
+            return 0;
+        };
+
+        // Captures and pawn moves cannot be undone, so no earlier position can repeat
+        self.positions
+            .iter()
+            .rev()
+            .take(self.halfmove_clock + 1)
+            .filter(|position| *position == current)
+            .count()
+    }
+
+    /// Whether the current position occurred three times, so that the side to move may claim a
+    /// draw
+    pub fn can_claim_threefold_repetition(&self) -> bool {
+        self.repetition_count() >= THREEFOLD_REPETITION
+    }
+
+    /// Whether the current position occurred five times, which ends the game in a draw
+    pub fn is_fivefold_repetition(&self) -> bool {
+        self.repetition_count() >= FIVEFOLD_REPETITION
+    }
+
+    /// Whether neither side has enough material left to checkmate, whatever the moves played: a
+    /// lone King against a King with at most one minor piece, or Bishops all standing on squares
+    /// of the same color
+    pub fn is_insufficient_material(&self) -> bool {
+        let mut minor_pieces = 0;
+        let mut bishop_square_colors = Vec::new();
+
+        for (piece, position) in self.board.into_iter() {
+            match piece.piece_type {
+                PieceType::King(_) => {}
+                PieceType::Knight => minor_pieces += 1,
+                PieceType::Bishop => {
+                    minor_pieces += 1;
+                    bishop_square_colors.push((position.line + position.col) % 2);
+                }
+                _ => return false,
+            }
+        }
+
+        minor_pieces <= 1
+            || (bishop_square_colors.len() == minor_pieces
+                && bishop_square_colors
+                    .windows(2)
+                    .all(|pair| pair[0] == pair[1]))
+    }
+
+    /// Claim a draw on behalf of the side to move, under the fifty-move rule or on threefold
+    /// repetition. Return whether the claim is valid, in which case the game is over. Taking back
+    /// a move withdraws the claim.
+    pub fn claim_draw(&mut self) -> bool {
+        let claim = if self.can_claim_fifty_move_draw() {
+            GameOutcome::FiftyMoveRule
+        }
+        else if self.can_claim_threefold_repetition() {
+            GameOutcome::ThreefoldRepetition
+        }
+        else {
+            return false;
+        };
+
+        self.declared_outcome = Some(claim);
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::MoveError;
+    use crate::pgn::GameResult;
+
+    const KING_SHUFFLE: [&str; 8] = ["Kf1", "Kf8", "Kg1", "Kg8", "Kf1", "Kf8", "Ke1", "Ke8"];
+
+    #[test]
+    fn test_fifty_move_rule_claim() -> Result<(), MoveError> {
+        let mut game_state = GameState::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 98 80").unwrap();
+
+        assert!(!game_state.claim_draw());
+
+        game_state.handle_move("Kf1")?;
+
+        assert!(!game_state.can_claim_fifty_move_draw());
+
+        game_state.handle_move("Kf8")?;
+
+        assert!(game_state.can_claim_fifty_move_draw());
+        assert!(!game_state.is_seventy_five_move_draw());
+        assert_eq!(game_state.result(), GameResult::Unknown);
+        assert!(game_state.claim_draw());
+        assert_eq!(game_state.result(), GameResult::Draw);
+
+        game_state.undo();
+
+        assert_eq!(game_state.outcome(), None);
+        assert_eq!(game_state.result(), GameResult::Unknown);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_clock_resets_on_pawn_moves_and_captures() -> Result<(), MoveError> {
+        let mut game_state = GameState::from_fen("4k3/8/8/8/3p4/8/4P3/R3K3 w - - 99 80").unwrap();
+
+        game_state.handle_move("e4")?;
+
+        assert_eq!(game_state.halfmove_clock(), 0);
+
+        game_state.handle_move("Kd7")?;
+        game_state.handle_move("Ra7+")?;
+
+        assert_eq!(game_state.halfmove_clock(), 2);
+
+        game_state.handle_move("Kd6")?;
+        game_state.handle_move("Rd7+")?;
+        game_state.handle_move("Kxd7")?;
+
+        assert_eq!(game_state.halfmove_clock(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_seventy_five_move_rule() -> Result<(), MoveError> {
+        let mut game_state = GameState::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 142 100").unwrap();
+
+        for san in KING_SHUFFLE {
+            assert!(!game_state.is_seventy_five_move_draw());
+
+            game_state.handle_move(san)?;
+        }
+
+        assert_eq!(game_state.halfmove_clock(), 150);
+        assert!(game_state.is_seventy_five_move_draw());
+        assert_eq!(game_state.result(), GameResult::Draw);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_threefold_and_fivefold_repetition() -> Result<(), MoveError> {
+        let mut game_state = GameState::starting_position();
+
+        for cycle in 1..=4 {
+            for san in ["Nf3", "Nf6", "Ng1", "Ng8"] {
+                game_state.handle_move(san)?;
+            }
+
+            assert_eq!(game_state.repetition_count(), cycle + 1);
+            assert_eq!(game_state.can_claim_threefold_repetition(), cycle >= 2);
+        }
+
+        assert_eq!(game_state.repetition_count(), 5);
+        assert!(game_state.is_fivefold_repetition());
+        assert_eq!(game_state.result(), GameResult::Draw);
+
+        for _ in 0..4 {
+            game_state.undo();
+        }
+
+        assert_eq!(game_state.repetition_count(), 4);
+        assert!(!game_state.is_fivefold_repetition());
+        assert!(game_state.can_claim_threefold_repetition());
+        assert!(game_state.claim_draw());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_repetition_requires_same_castling_rights() -> Result<(), MoveError> {
+        let mut game_state = GameState::from_fen("r3k3/8/8/8/8/8/8/4K2R w Kq - 0 1").unwrap();
+
+        for san in ["Rh2", "Ra7", "Rh1", "Ra8"] {
+            game_state.handle_move(san)?;
+        }
+
+        assert_eq!(game_state.repetition_count(), 1);
+
+        for san in ["Rh2", "Ra7", "Rh1", "Ra8", "Rh2", "Ra7", "Rh1", "Ra8"] {
+            game_state.handle_move(san)?;
+        }
+
+        assert_eq!(game_state.repetition_count(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_repetition_ignores_impossible_en_passant() -> Result<(), MoveError> {
+        let mut game_state = GameState::from_fen("4k3/8/8/8/8/8/4P3/4K1N1 w - - 0 1").unwrap();
+
+        game_state.handle_move("e4")?;
+        for san in ["Kd7", "Nf3", "Ke8", "Ng1"] {
+            game_state.handle_move(san)?;
+        }
+
+        assert_eq!(game_state.repetition_count(), 2);
+
+        let mut game_state = GameState::from_fen("4k3/8/8/8/3p4/8/4P3/4K1N1 w - - 0 1").unwrap();
+
+        game_state.handle_move("e4")?;
+        for san in ["Kd7", "Nf3", "Ke8", "Ng1"] {
+            game_state.handle_move(san)?;
+        }
+
+        assert_eq!(game_state.repetition_count(), 1);
+
+        for san in ["Kd7", "Nf3", "Ke8", "Ng1"] {
+            game_state.handle_move(san)?;
+        }
+
+        assert_eq!(game_state.repetition_count(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_insufficient_material() {
+        let insufficient = [
+            "4k3/8/8/8/8/8/8/4K3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/2B1K3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/4K1n1 b - - 0 1",
+            "2b1k3/8/8/8/8/8/8/3BK3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/B1B1K3 w - - 0 1",
+        ];
+        let sufficient = [
+            "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/R3K3 w - - 0 1",
+            "3bk3/8/8/8/8/8/8/3BK3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/1NB1K3 w - - 0 1",
+            "4kn2/8/8/8/8/8/8/2B1K3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/1NN1K3 w - - 0 1",
+        ];
+
+        for fen in insufficient {
+            let game_state = GameState::from_fen(fen).unwrap();
+
+            assert!(game_state.is_insufficient_material(), "{fen}");
+            assert_eq!(game_state.result(), GameResult::Draw, "{fen}");
+        }
+        for fen in sufficient {
+            let game_state = GameState::from_fen(fen).unwrap();
+
+            assert!(!game_state.is_insufficient_material(), "{fen}");
+            assert_eq!(game_state.result(), GameResult::Unknown, "{fen}");
+        }
+    }
+
+    #[test]
+    fn test_checkmate_prevails_over_seventy_five_move_rule() -> Result<(), MoveError> {
+        let mut game_state = GameState::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 149 100").unwrap();
+
+        game_state.handle_move("Ra8#")?;
+
+        assert!(!game_state.is_seventy_five_move_draw());
+        assert_eq!(game_state.result(), GameResult::WhiteWins);
+
+        Ok(())
+    }
+}
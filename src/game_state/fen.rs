@@ -0,0 +1,518 @@
+use super::GameState;
+use crate::board::constants::{BOARD_SIZE, LINES};
+use crate::errors::FenError;
+use crate::errors::constants::INTERNAL_ERROR_01;
+use crate::movement::{ChessPosition, Position};
+use crate::pieces::types::{King, Rook};
+use crate::pieces::{Color, Piece, PieceType};
+
+/// FEN of the standard starting position
+pub const STARTING_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+const EMPTY_FIELD: &str = "-";
+const RANK_SEPARATOR: char = '/';
+const WHITE_PAWN_INITIAL_LINE: usize = BOARD_SIZE - 2;
+const BLACK_PAWN_INITIAL_LINE: usize = 1;
+
+impl GameState {
+    /// Build a game state from a position in Forsyth-Edwards Notation. The halfmove clock and
+    /// fullmove number fields are optional and default to 0 and 1.
+    pub fn from_fen(fen: &str) -> Result<GameState, FenError> {
+        let mut fields = fen.split_whitespace();
+        let mut game_state = GameState::new();
+
+        let placement = fields
+            .next()
+            .ok_or(FenError::MissingField("piece placement"))?;
+        game_state.parse_placement(placement)?;
+
+        game_state.turn = match fields.next() {
+            Some("w") => Color::White,
+            Some("b") => Color::Black,
+            Some(_) => return Err(FenError::InvalidActiveColor),
+            None => return Err(FenError::MissingField("active color")),
+        };
+
+        let castling_rights = fields
+            .next()
+            .ok_or(FenError::MissingField("castling rights"))?;
+        game_state.parse_castling_rights(castling_rights)?;
+
+        let en_passant = fields.next().ok_or(FenError::MissingField("en passant"))?;
+        game_state.en_passant_target = game_state.parse_en_passant(en_passant)?;
+
+        if let Some(halfmove_clock) = fields.next() {
+            game_state.halfmove_clock = halfmove_clock
+                .parse()
+                .map_err(|_| FenError::InvalidClock("halfmove"))?;
+        }
+
+        if let Some(fullmove_number) = fields.next() {
+            game_state.fullmove_number = fullmove_number
+                .parse()
+                .ok()
+                .filter(|number| *number > 0)
+                .ok_or(FenError::InvalidClock("fullmove"))?;
+        }
+
+        if fields.next().is_some() {
+            return Err(FenError::TooManyFields);
+        }
+
+        game_state
+            .validate_setup()
+            .map_err(FenError::IllegalPosition)?;
+        game_state.finish_setup();
+
+        Ok(game_state)
+    }
+
+    /// Describe the current position in Forsyth-Edwards Notation
+    pub fn to_fen(&self) -> String {
+        let placement = (0..BOARD_SIZE)
+            .map(|line| self.rank_to_fen(line))
+            .collect::<Vec<String>>()
+            .join(&RANK_SEPARATOR.to_string());
+
+        let active_color = match self.turn {
+            Color::White => 'w',
+            Color::Black => 'b',
+        };
+
+        let castling_rights: String = [
+            (Color::White, true, 'K'),
+            (Color::White, false, 'Q'),
+            (Color::Black, true, 'k'),
+            (Color::Black, false, 'q'),
+        ]
+        .into_iter()
+        .filter(|(color, is_short_castle, _)| self.has_castling_rights(*color, *is_short_castle))
+        .map(|(_, _, symbol)| symbol)
+        .collect();
+
+        let en_passant = match self.en_passant_target {
+            Some(target) => {
+                let chess_pos: ChessPosition = target.try_into().expect(INTERNAL_ERROR_01);
+                format!("{}{}", chess_pos.col, chess_pos.line)
+            }
+            None => EMPTY_FIELD.to_owned(),
+        };
+
+        format!(
+            "{placement} {active_color} {} {en_passant} {} {}",
+            if castling_rights.is_empty() {
+                EMPTY_FIELD
+            }
+            else {
+                &castling_rights
+            },
+            self.halfmove_clock,
+            self.fullmove_number
+        )
+    }
+
+    fn rank_to_fen(&self, line: usize) -> String {
+        let mut rank = String::new();
+        let mut empty_squares = 0;
+
+        for col in 0..BOARD_SIZE {
+            match self.board.get_piece(Position::new(line, col)) {
+                Some(piece) => {
+                    if empty_squares > 0 {
+                        rank.push_str(&empty_squares.to_string());
+                        empty_squares = 0;
+                    }
+                    rank.push(piece_to_fen(&piece));
+                }
+                None => empty_squares += 1,
+            }
+        }
+
+        if empty_squares > 0 {
+            rank.push_str(&empty_squares.to_string());
+        }
+
+        rank
+    }
+
+    fn parse_placement(&mut self, placement: &str) -> Result<(), FenError> {
+        let ranks: Vec<&str> = placement.split(RANK_SEPARATOR).collect();
+        if ranks.len() != BOARD_SIZE {
+            return Err(FenError::InvalidRankCount(ranks.len()));
+        }
+
+        for (line, rank) in ranks.into_iter().enumerate() {
+            let mut col = 0;
+
+            for fen_char in rank.chars() {
+                if let Some(empty_squares) = fen_char.to_digit(10)
+                    && (1..=BOARD_SIZE as u32).contains(&empty_squares)
+                {
+                    col += empty_squares as usize;
+                    continue;
+                }
+
+                if col >= BOARD_SIZE {
+                    return Err(FenError::InvalidRank(LINES[line]));
+                }
+
+                let position = Position::new(line, col);
+                self.board
+                    .add_piece(piece_from_fen(fen_char, position)?, position);
+                col += 1;
+            }
+
+            if col != BOARD_SIZE {
+                return Err(FenError::InvalidRank(LINES[line]));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_castling_rights(&mut self, castling_rights: &str) -> Result<(), FenError> {
+        if castling_rights == EMPTY_FIELD {
+            return Ok(());
+        }
+
+        for symbol in castling_rights.chars() {
+            let (color, is_short_castle) = match symbol {
+                'K' => (Color::White, true),
+                'Q' => (Color::White, false),
+                'k' => (Color::Black, true),
+                'q' => (Color::Black, false),
+                _ => return Err(FenError::InvalidCastlingRights(symbol)),
+            };
+
+            let (king_source, _) = King::get_castle_move(color, is_short_castle);
+            let (rook_source, _) = Rook::get_castle_move(color, is_short_castle);
+
+            match self.board.get_piece_as_mut(king_source) {
+                Some(Piece {
+                    piece_type: PieceType::King(king),
+                    color: king_color,
+                    ..
+                }) if *king_color == color => {
+                    if is_short_castle {
+                        king.short_castling_available = true;
+                    }
+                    else {
+                        king.long_castling_available = true;
+                    }
+                }
+                _ => return Err(FenError::InvalidCastlingRights(symbol)),
+            }
+
+            match self.board.get_piece_as_mut(rook_source) {
+                Some(Piece {
+                    piece_type: PieceType::Rook(rook),
+                    color: rook_color,
+                    ..
+                }) if *rook_color == color => {
+                    rook.short_castling_available = true;
+                    rook.long_castling_available = true;
+                }
+                _ => return Err(FenError::InvalidCastlingRights(symbol)),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_en_passant(&self, en_passant: &str) -> Result<Option<Position>, FenError> {
+        if en_passant == EMPTY_FIELD {
+            return Ok(None);
+        }
+
+        let mut chars = en_passant.chars();
+        let (Some(col), Some(line), None) = (chars.next(), chars.next(), chars.next())
+        else {
+            return Err(FenError::InvalidEnPassantSquare);
+        };
+
+        let target: Position = ChessPosition::new(line, col)
+            .try_into()
+            .map_err(|_| FenError::InvalidEnPassantSquare)?;
+
+        // The skipped square sits right behind the pawn of the side that just moved
+        let expected_line = match self.turn {
+            Color::White => BLACK_PAWN_INITIAL_LINE + 1,
+            Color::Black => WHITE_PAWN_INITIAL_LINE - 1,
+        };
+        if target.line != expected_line {
+            return Err(FenError::InvalidEnPassantSquare);
+        }
+
+        // The pawn stands in front of the skipped square, and the squares it crossed are empty
+        let (pawn_line, source_line) = match self.turn {
+            Color::White => (target.line + 1, target.line - 1),
+            Color::Black => (target.line - 1, target.line + 1),
+        };
+        let has_pawn = self
+            .board
+            .get_piece(Position::new(pawn_line, target.col))
+            .is_some_and(|piece| {
+                piece.color != self.turn && matches!(piece.piece_type, PieceType::Pawn(_))
+            });
+        if !has_pawn
+            || self.board.is_position_occupied(target)
+            || self
+                .board
+                .is_position_occupied(Position::new(source_line, target.col))
+        {
+            return Err(FenError::InvalidEnPassantSquare);
+        }
+
+        Ok(Some(target))
+    }
+}
+
+fn piece_from_fen(fen_char: char, position: Position) -> Result<Piece, FenError> {
+    let color = if fen_char.is_ascii_uppercase() {
+        Color::White
+    }
+    else {
+        Color::Black
+    };
+
+    let piece_type = PieceType::try_from(fen_char.to_ascii_uppercase())
+        .ok()
+        .filter(|piece_type| !matches!(piece_type, PieceType::None))
+        .ok_or(FenError::InvalidPiece(fen_char))?;
+
+    let mut piece = Piece::new(piece_type, color);
+
+    // Castling rights come from their own field, and only pawns on their initial line may
+    // advance two squares
+    piece.deny_castling_rights();
+
+    let initial_line = match color {
+        Color::White => WHITE_PAWN_INITIAL_LINE,
+        Color::Black => BLACK_PAWN_INITIAL_LINE,
+    };
+    if position.line != initial_line {
+        piece.deny_two_rows();
+    }
+
+    Ok(piece)
+}
+
+fn piece_to_fen(piece: &Piece) -> char {
+    let letter = piece.piece_type.letter();
+
+    match piece.color {
+        Color::White => letter,
+        Color::Black => letter.to_ascii_lowercase(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::{MoveError, SetupErrorKind};
+    use crate::utils::test_helper::{setup_game_state, setup_game_state_with_turn};
+
+    const FIXTURES: [(&str, Color); 16] = [
+        ("initial_positions.txt", Color::White),
+        ("tests/check_can_be_blocked.txt", Color::Black),
+        ("tests/en_passant_escapes_checkmate.txt", Color::White),
+        ("tests/en_passant_only_move.txt", Color::White),
+        ("tests/king_can_escape.txt", Color::Black),
+        ("tests/not_stalemate_capture_only.txt", Color::White),
+        ("tests/pinned_piece_exposes_king.txt", Color::White),
+        ("tests/promotion.txt", Color::White),
+        ("tests/scholars_mate.txt", Color::Black),
+        ("tests/stalemate_pinned_piece.txt", Color::White),
+        ("tests/validate_castling_path_fail.txt", Color::White),
+        ("tests/validate_castling_path_success.txt", Color::White),
+        (
+            "tests/validate_disambiguation_same_column.txt",
+            Color::White,
+        ),
+        ("tests/validate_disambiguation_same_line.txt", Color::White),
+        (
+            "tests/validate_not_stalemate_with_legal_move.txt",
+            Color::White,
+        ),
+        ("tests/validate_stalemate.txt", Color::White),
+    ];
+
+    #[test]
+    fn test_starting_position_to_fen() {
+        let game_state = setup_game_state(None);
+
+        assert_eq!(game_state.to_fen(), STARTING_FEN);
+    }
+
+    #[test]
+    fn test_starting_position_from_fen() -> Result<(), FenError> {
+        let game_state = GameState::from_fen(STARTING_FEN)?;
+
+        assert_eq!(game_state, setup_game_state(None));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_fen_after_moves() -> Result<(), MoveError> {
+        let mut game_state = setup_game_state(None);
+
+        game_state.handle_move("e4")?;
+        assert_eq!(
+            game_state.to_fen(),
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+        );
+
+        game_state.handle_move("c5")?;
+        game_state.handle_move("Nf3")?;
+        assert_eq!(
+            game_state.to_fen(),
+            "rnbqkbnr/pp1ppppp/8/2p5/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2"
+        );
+
+        game_state.handle_move("Nc6")?;
+        game_state.handle_move("Ke2")?;
+        assert_eq!(
+            game_state.to_fen(),
+            "r1bqkbnr/pp1ppppp/2n5/2p5/4P3/5N2/PPPPKPPP/RNBQ1B1R b kq - 3 3"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fixtures_round_trip() -> Result<(), FenError> {
+        for (fixture, turn) in FIXTURES {
+            let game_state = setup_game_state_with_turn(Some(fixture), turn);
+            let fen = game_state.to_fen();
+
+            let imported = GameState::from_fen(&fen)?;
+
+            assert_eq!(imported.to_fen(), fen, "{fixture}");
+            assert_eq!(
+                imported.board().into_iter().count(),
+                game_state.board().into_iter().count(),
+                "{fixture}"
+            );
+            for (piece, position) in game_state.board() {
+                let imported_piece = imported.get_piece(position).unwrap();
+
+                assert_eq!(
+                    imported_piece.piece_type.letter(),
+                    piece.piece_type.letter()
+                );
+                assert_eq!(imported_piece.color, piece.color);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_fen_fields() -> Result<(), FenError> {
+        let game_state = GameState::from_fen("r3k2r/8/8/3pP3/8/8/8/R3K2R w Kq d6 4 20")?;
+
+        assert!(game_state.is_white_turn());
+        assert!(game_state.has_castling_rights(Color::White, true));
+        assert!(!game_state.has_castling_rights(Color::White, false));
+        assert!(!game_state.has_castling_rights(Color::Black, true));
+        assert!(game_state.has_castling_rights(Color::Black, false));
+        assert_eq!(game_state.en_passant_target(), Some(Position::new(2, 3)));
+        assert_eq!(game_state.halfmove_clock(), 4);
+        assert_eq!(game_state.fullmove_number(), 20);
+        assert_eq!(game_state.white_king_position, Position::new(7, 4));
+        assert_eq!(game_state.black_king_position, Position::new(0, 4));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_fen_without_clocks() -> Result<(), FenError> {
+        let game_state = GameState::from_fen("4k3/8/8/8/8/8/8/4K3 b - -")?;
+
+        assert!(game_state.is_black_turn());
+        assert_eq!(game_state.halfmove_clock(), 0);
+        assert_eq!(game_state.fullmove_number(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_fen_errors() {
+        let cases = [
+            ("", FenError::MissingField("piece placement")),
+            ("8/8/8/8/8/8/8/8", FenError::MissingField("active color")),
+            ("8/8/8/8/8/8/8 w - - 0 1", FenError::InvalidRankCount(7)),
+            ("8/8/8/8/8/8/8/7 w - - 0 1", FenError::InvalidRank('1')),
+            ("8/8/8/8/8/8/8/44k w - - 0 1", FenError::InvalidRank('1')),
+            ("8/8/8/8/8/8/8/7x w - - 0 1", FenError::InvalidPiece('x')),
+            ("8/8/8/8/8/8/8/8 x - - 0 1", FenError::InvalidActiveColor),
+            (
+                "8/8/8/8/8/8/8/4K3 w K - 0 1",
+                FenError::InvalidCastlingRights('K'),
+            ),
+            (
+                "8/8/8/8/8/8/8/8 w X - 0 1",
+                FenError::InvalidCastlingRights('X'),
+            ),
+            (
+                "8/8/8/8/8/8/8/8 w - e3 0 1",
+                FenError::InvalidEnPassantSquare,
+            ),
+            (
+                "8/8/8/8/8/8/8/8 w - z6 0 1",
+                FenError::InvalidEnPassantSquare,
+            ),
+            // A knight rather than a pawn in front of the square
+            (
+                "4k3/8/8/3Pn3/8/8/8/4K3 w - e6 0 1",
+                FenError::InvalidEnPassantSquare,
+            ),
+            // The skipped square is occupied
+            (
+                "4k3/8/4p3/3Pn3/8/8/8/4K3 w - e6 0 1",
+                FenError::InvalidEnPassantSquare,
+            ),
+            // The square the pawn came from is occupied
+            (
+                "4k3/4n3/8/3Pp3/8/8/8/4K3 w - e6 0 1",
+                FenError::InvalidEnPassantSquare,
+            ),
+            (
+                "8/8/8/8/8/8/8/8 w - - x 1",
+                FenError::InvalidClock("halfmove"),
+            ),
+            (
+                "8/8/8/8/8/8/8/8 w - - 0 0",
+                FenError::InvalidClock("fullmove"),
+            ),
+            ("8/8/8/8/8/8/8/8 w - - 0 1 1", FenError::TooManyFields),
+            (
+                "4k3/8/8/8/8/8/8/8 w - - 0 1",
+                FenError::IllegalPosition(SetupErrorKind::MissingKing(Color::White)),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/P3K3 w - - 0 1",
+                FenError::IllegalPosition(SetupErrorKind::PawnOnBackRank),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/4R1K1 w - - 0 1",
+                FenError::IllegalPosition(SetupErrorKind::OpponentInCheck(Color::Black)),
+            ),
+        ];
+
+        for (fen, expected_error) in cases {
+            assert_eq!(GameState::from_fen(fen), Err(expected_error), "{fen}");
+        }
+    }
+
+    #[test]
+    fn test_from_fen_plays_en_passant() -> Result<(), MoveError> {
+        let mut game_state = GameState::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+
+        game_state.handle_move("exd6")?;
+
+        assert_eq!(game_state.to_fen(), "4k3/8/3P4/8/8/8/8/4K3 b - - 0 1");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,207 @@
+use super::GameState;
+use crate::pgn::GameResult;
+use crate::pieces::Color;
+use std::fmt::Display;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+/// Way a game ended, along with the winner of decisive games
+pub enum GameOutcome {
+    Checkmate(Color),
+    Stalemate,
+    /// Draw claimed after 50 moves of each player without any capture or pawn move
+    FiftyMoveRule,
+    /// Draw after 75 moves of each player without any capture or pawn move
+    SeventyFiveMoveRule,
+    /// Draw claimed when the same position occurred three times
+    ThreefoldRepetition,
+    /// Draw when the same position occurred five times
+    FivefoldRepetition,
+    InsufficientMaterial,
+    Resignation(Color),
+    Timeout(Color),
+}
+
+impl GameOutcome {
+    /// Winner of the game, or `None` for a draw
+    pub fn winner(&self) -> Option<Color> {
+        match self {
+            Self::Checkmate(winner) | Self::Resignation(winner) | Self::Timeout(winner) => {
+                Some(*winner)
+            }
+            _ => None,
+        }
+    }
+
+    /// Result of the game, as written at the end of its movetext
+    pub fn result(&self) -> GameResult {
+        match self.winner() {
+            Some(Color::White) => GameResult::WhiteWins,
+            Some(Color::Black) => GameResult::BlackWins,
+            None => GameResult::Draw,
+        }
+    }
+}
+
+impl Display for GameOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Checkmate(winner) => write!(f, "Checkmate! {winner} wins"),
+            Self::Stalemate => write!(f, "Stalemate!"),
+            Self::FiftyMoveRule => write!(f, "Draw by fifty-move rule"),
+            Self::SeventyFiveMoveRule => write!(f, "Draw by seventy-five-move rule"),
+            Self::ThreefoldRepetition => write!(f, "Draw by threefold repetition"),
+            Self::FivefoldRepetition => write!(f, "Draw by fivefold repetition"),
+            Self::InsufficientMaterial => write!(f, "Draw by insufficient material"),
+            Self::Resignation(winner) => write!(f, "{} resigns, {winner} wins", winner.opposite()),
+            Self::Timeout(winner) => {
+                write!(f, "{} ran out of time, {winner} wins", winner.opposite())
+            }
+        }
+    }
+}
+
+impl GameState {
+    /// How the game ended, or `None` while it is still in progress. A claimed draw, a resignation
+    /// or a timeout prevails, then checkmate, stalemate and the automatic draw rules.
+    pub fn outcome(&self) -> Option<GameOutcome> {
+        if self.declared_outcome.is_some() {
+            return self.declared_outcome;
+        }
+
+        if self.verify_checkmate() {
+            Some(GameOutcome::Checkmate(self.turn.opposite()))
+        }
+        else if self.is_stalemate() {
+            Some(GameOutcome::Stalemate)
+        }
+        else if self.is_seventy_five_move_draw() {
+            Some(GameOutcome::SeventyFiveMoveRule)
+        }
+        else if self.is_fivefold_repetition() {
+            Some(GameOutcome::FivefoldRepetition)
+        }
+        else if self.is_insufficient_material() {
+            Some(GameOutcome::InsufficientMaterial)
+        }
+        else {
+            None
+        }
+    }
+
+    /// Whether the game is over, whichever way it ended
+    pub fn is_over(&self) -> bool {
+        self.outcome().is_some()
+    }
+
+    /// End the game with the resignation of the side to move
+    pub fn resign(&mut self) {
+        self.declared_outcome = Some(GameOutcome::Resignation(self.turn.opposite()));
+    }
+
+    /// End the game with the given player running out of time
+    pub fn lose_on_time(&mut self, loser: Color) {
+        self.declared_outcome = Some(GameOutcome::Timeout(loser.opposite()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::MoveError;
+
+    #[test]
+    fn test_outcome_of_finished_positions() {
+        let outcomes = [
+            (
+                "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3",
+                Some(GameOutcome::Checkmate(Color::Black)),
+            ),
+            (
+                "7k/5Q2/8/8/8/8/8/K7 b - - 0 1",
+                Some(GameOutcome::Stalemate),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/R3K3 w - - 150 100",
+                Some(GameOutcome::SeventyFiveMoveRule),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/2B1K3 w - - 0 1",
+                Some(GameOutcome::InsufficientMaterial),
+            ),
+            ("4k3/8/8/8/8/8/8/R3K3 w - - 100 100", None),
+        ];
+
+        for (fen, outcome) in outcomes {
+            assert_eq!(
+                GameState::from_fen(fen).unwrap().outcome(),
+                outcome,
+                "{fen}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_declared_outcomes() -> Result<(), MoveError> {
+        let mut game_state = GameState::starting_position();
+        game_state.handle_move("e4")?;
+
+        assert_eq!(game_state.outcome(), None);
+
+        game_state.resign();
+
+        assert_eq!(
+            game_state.outcome(),
+            Some(GameOutcome::Resignation(Color::White))
+        );
+        assert_eq!(game_state.result(), GameResult::WhiteWins);
+
+        game_state.undo();
+        game_state.lose_on_time(Color::White);
+
+        assert_eq!(
+            game_state.outcome(),
+            Some(GameOutcome::Timeout(Color::Black))
+        );
+        assert_eq!(game_state.result(), GameResult::BlackWins);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_move_after_the_game_is_over() -> Result<(), MoveError> {
+        let mut game_state = GameState::starting_position();
+        game_state.handle_move("e4")?;
+        let next_move = game_state.legal_moves()[0];
+
+        game_state.resign();
+
+        assert_eq!(game_state.handle_move("e5"), Err(MoveError::GameOver));
+        assert_eq!(game_state.play_move(next_move), Err(MoveError::GameOver));
+        assert_eq!(game_state.history().len(), 1);
+
+        game_state.undo();
+        game_state.handle_move("e4")?;
+        game_state.lose_on_time(Color::Black);
+
+        assert_eq!(game_state.handle_move("e5"), Err(MoveError::GameOver));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            GameOutcome::Checkmate(Color::White).to_string(),
+            "Checkmate! White wins"
+        );
+        assert_eq!(
+            GameOutcome::FiftyMoveRule.to_string(),
+            "Draw by fifty-move rule"
+        );
+        assert_eq!(
+            GameOutcome::Resignation(Color::Black).to_string(),
+            "White resigns, Black wins"
+        );
+        assert_eq!(GameOutcome::Stalemate.result(), GameResult::Draw);
+    }
+}
@@ -0,0 +1,129 @@
+use super::GameState;
+use crate::movement::Move;
+
+impl GameState {
+    /// Count the leaf nodes of the legal move tree down to the given depth, which is compared
+    /// against published counts to validate move generation
+    pub fn perft(&self, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        let legal_moves = self.legal_moves();
+        if depth == 1 {
+            return legal_moves.len() as u64;
+        }
+
+        legal_moves
+            .into_iter()
+            .map(|next_move| self.child(next_move).perft(depth - 1))
+            .sum()
+    }
+
+    /// Split the perft count by the legal moves of the side to move, to locate which subtree
+    /// differs from a reference move generator, and return the total count along. No move is
+    /// played at depth 0, where the count is the single root node.
+    pub fn divide(&self, depth: usize) -> (Vec<(Move, u64)>, u64) {
+        if depth == 0 {
+            return (Vec::new(), 1);
+        }
+
+        let moves: Vec<(Move, u64)> = self
+            .legal_moves()
+            .into_iter()
+            .map(|next_move| (next_move, self.child(next_move).perft(depth - 1)))
+            .collect();
+        let nodes = moves.iter().map(|(_, move_nodes)| move_nodes).sum();
+
+        (moves, nodes)
+    }
+
+    fn child(&self, next_move: Move) -> GameState {
+        let mut game_state = self.clone();
+        game_state.apply_move(next_move);
+
+        game_state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reference positions from the Chess Programming Wiki, with their node counts by depth
+    const REFERENCE_POSITIONS: [(&str, &[u64]); 6] = [
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            &[20, 400, 8902],
+        ),
+        (
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            &[48, 2039, 97862],
+        ),
+        (
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            &[14, 191, 2812],
+        ),
+        (
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            &[6, 264, 9467],
+        ),
+        (
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            &[44, 1486, 62379],
+        ),
+        (
+            "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+            &[46, 2079, 89890],
+        ),
+    ];
+
+    #[test]
+    fn test_reference_positions() {
+        for (fen, node_counts) in REFERENCE_POSITIONS {
+            let game_state = GameState::from_fen(fen).unwrap();
+
+            for (depth, nodes) in node_counts.iter().enumerate() {
+                assert_eq!(
+                    game_state.perft(depth + 1),
+                    *nodes,
+                    "depth {} of {fen}",
+                    depth + 1
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_divide_sums_to_perft() {
+        let game_state = GameState::starting_position();
+
+        for depth in 0..=2 {
+            let (moves, nodes) = game_state.divide(depth);
+
+            assert_eq!(nodes, game_state.perft(depth), "depth {depth}");
+            if depth > 0 {
+                assert_eq!(moves.iter().map(|(_, nodes)| nodes).sum::<u64>(), nodes);
+            }
+        }
+
+        let (moves, nodes) = game_state.divide(3);
+        assert_eq!(moves.len(), 20);
+        assert_eq!(nodes, 8902);
+
+        let (_, e2e4_nodes) = moves
+            .iter()
+            .find(|(next_move, _)| next_move.to_string() == "e2e4")
+            .unwrap();
+
+        assert_eq!(*e2e4_nodes, 600);
+    }
+
+    #[test]
+    fn test_depth_zero() {
+        let game_state = GameState::starting_position();
+
+        assert_eq!(game_state.perft(0), 1);
+        assert_eq!(game_state.divide(0), (Vec::new(), 1));
+    }
+}
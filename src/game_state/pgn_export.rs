@@ -0,0 +1,177 @@
+use super::GameState;
+use super::fen::STARTING_FEN;
+use crate::pgn::constants::{BLACK_MOVE_NUMBER_DOTS, FEN_TAG, MOVE_NUMBER_DOT, SETUP_TAG};
+use crate::pgn::{GameResult, PgnTags};
+use std::mem::take;
+
+const MAX_LINE_LENGTH: usize = 80;
+
+impl GameState {
+    /// Result of the game in its current state, which is unknown while the game is in progress
+    pub fn result(&self) -> GameResult {
+        self.outcome()
+            .map_or(GameResult::Unknown, |outcome| outcome.result())
+    }
+
+    /// Describe the game played so far in PGN: the Seven Tag Roster, the starting position when
+    /// it is not the standard one, and the numbered movetext wrapped at 80 columns
+    pub fn to_pgn(&self, tags: &PgnTags) -> String {
+        let result = self.result().to_string();
+
+        let mut pgn = String::new();
+        for (name, value) in tags.pairs() {
+            pgn.push_str(&tag_pair(name, value));
+        }
+        pgn.push_str(&tag_pair("Result", &result));
+        if self.initial_fen != STARTING_FEN {
+            pgn.push_str(&tag_pair(SETUP_TAG, "1"));
+            pgn.push_str(&tag_pair(FEN_TAG, &self.initial_fen));
+        }
+        pgn.push('\n');
+
+        let mut tokens = self.movetext_tokens();
+        tokens.push(result);
+        pgn.push_str(&wrap(&tokens));
+        pgn.push('\n');
+
+        pgn
+    }
+
+    fn movetext_tokens(&self) -> Vec<String> {
+        // Plies counted from White's first move, which is how move numbers are derived
+        let current_ply = 2 * (self.fullmove_number - 1) + usize::from(self.is_black_turn());
+        let first_ply = current_ply - self.history.len();
+
+        let mut tokens = Vec::new();
+        for (index, played_move) in self.history.iter().enumerate() {
+            let ply = first_ply + index;
+            let move_number = ply / 2 + 1;
+
+            if ply.is_multiple_of(2) {
+                tokens.push(format!("{move_number}{MOVE_NUMBER_DOT}"));
+            }
+            else if index == 0 {
+                tokens.push(format!("{move_number}{BLACK_MOVE_NUMBER_DOTS}"));
+            }
+            tokens.push(played_move.san.clone());
+        }
+
+        tokens
+    }
+}
+
+fn tag_pair(name: &str, value: &str) -> String {
+    let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+
+    format!("[{name} \"{value}\"]\n")
+}
+
+fn wrap(tokens: &[String]) -> String {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+
+    for token in tokens {
+        if !line.is_empty() && line.len() + 1 + token.len() > MAX_LINE_LENGTH {
+            lines.push(take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(token);
+    }
+    lines.push(line);
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::{MoveError, PgnReadError};
+    use crate::pgn::games_from_str;
+
+    #[test]
+    fn test_empty_game() {
+        let game_state = GameState::starting_position();
+
+        assert_eq!(
+            game_state.to_pgn(&PgnTags::default()),
+            "[Event \"?\"]\n[Site \"?\"]\n[Date \"????.??.??\"]\n[Round \"?\"]\n\
+             [White \"?\"]\n[Black \"?\"]\n[Result \"*\"]\n\n*\n"
+        );
+    }
+
+    #[test]
+    fn test_checkmate_result_and_tags() -> Result<(), MoveError> {
+        let mut game_state = GameState::starting_position();
+        for san in ["f3", "e5", "g4", "Qh4#"] {
+            game_state.handle_move(san)?;
+        }
+        let tags = PgnTags {
+            event: "Club \"blitz\"".to_owned(),
+            white: "Student".to_owned(),
+            ..Default::default()
+        };
+
+        let pgn = game_state.to_pgn(&tags);
+
+        assert!(pgn.starts_with("[Event \"Club \\\"blitz\\\"\"]\n"));
+        assert!(pgn.contains("[White \"Student\"]\n[Black \"?\"]\n[Result \"0-1\"]\n\n"));
+        assert!(pgn.ends_with("\n1. f3 e5 2. g4 Qh4# 0-1\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stalemate_result() -> Result<(), MoveError> {
+        let mut game_state = GameState::from_fen("7k/8/6Q1/8/8/8/8/K7 w - - 0 1").unwrap();
+        game_state.handle_move("Qf7")?;
+
+        let pgn = game_state.to_pgn(&PgnTags::default());
+
+        assert!(pgn.contains("[Result \"1/2-1/2\"]\n"));
+        assert!(pgn.ends_with("\n1. Qf7 1/2-1/2\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_start_with_black_to_move() -> Result<(), MoveError> {
+        let fen = "4k3/8/8/8/8/8/4P3/4K3 b - - 0 12";
+        let mut game_state = GameState::from_fen(fen).unwrap();
+        game_state.handle_move("Kd7")?;
+        game_state.handle_move("e4")?;
+
+        let pgn = game_state.to_pgn(&PgnTags::default());
+
+        assert!(pgn.contains(&format!("[SetUp \"1\"]\n[FEN \"{fen}\"]\n\n")));
+        assert!(pgn.ends_with("\n12... Kd7 13. e4 *\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_movetext_is_wrapped_and_read_back() -> Result<(), PgnReadError> {
+        let moves = [
+            "e4", "e5", "Nf3", "Nc6", "Bb5", "a6", "Ba4", "Nf6", "O-O", "Be7", "Re1", "b5", "Bb3",
+            "d6", "c3", "O-O", "h3", "Nb8", "d4", "Nbd7", "c4", "c6", "cxb5", "axb5", "Nc3", "Bb7",
+            "Bg5", "b4", "Nb1", "h6",
+        ];
+        let mut game_state = GameState::starting_position();
+        for san in moves {
+            game_state.handle_move(san).unwrap();
+        }
+
+        let pgn = game_state.to_pgn(&PgnTags::default());
+
+        assert!(pgn.lines().all(|line| line.len() <= MAX_LINE_LENGTH));
+        assert!(pgn.lines().filter(|line| !line.starts_with('[')).count() > 2);
+
+        let games = games_from_str(&pgn)?;
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].moves(), moves);
+        assert_eq!(games[0].replay()?.to_fen(), game_state.to_fen());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use super::GameState;
+use crate::board::square;
+use crate::errors::MoveError;
+use crate::errors::constants::INTERNAL_ERROR_07;
+use crate::movement::{Move, Position};
+use crate::pieces::PieceType;
+
+impl GameState {
+    /// Find the legal move of the piece on `source` to `destination`, for the notations naming
+    /// both squares, or explain why there is none
+    pub(crate) fn resolve_move(
+        &self,
+        source: Position,
+        destination: Position,
+        promotion: Option<PieceType>,
+    ) -> Result<Move, MoveError> {
+        if !self
+            .board
+            .get_piece(source)
+            .is_some_and(|piece| piece.color == self.turn)
+        {
+            return Err(MoveError::NoPieceAvailable);
+        }
+
+        let candidates: Vec<Move> = self
+            .legal_moves()
+            .into_iter()
+            .filter(|next_move| {
+                next_move.source() == source && next_move.destination() == destination
+            })
+            .collect();
+
+        if candidates.is_empty() {
+            return Err(self.illegal_move_error(source, destination));
+        }
+
+        let next_move = match promotion {
+            Some(promotion) => candidates
+                .into_iter()
+                .find(|next_move| next_move.promotion == Some(promotion)),
+            None => candidates.into_iter().next(),
+        };
+
+        match next_move {
+            Some(next_move) if next_move.promotion == promotion => Ok(next_move),
+            // Reuse the diagnostics of SAN promotions
+            _ => {
+                let attempt = Move {
+                    promotion,
+                    ..self.build_move(source, destination)
+                };
+                self.validate_promotion(attempt)?;
+                Err(MoveError::InvalidPromotion(
+                    "A pawn can only be promoted to a Queen, Rook, Bishop or Knight",
+                ))
+            }
+        }
+    }
+
+    /// Explain why the piece of the side to move on `source` cannot legally go to `destination`
+    fn illegal_move_error(&self, source: Position, destination: Position) -> MoveError {
+        let piece = self.board.get_piece(source).expect(INTERNAL_ERROR_07);
+
+        if source == destination {
+            return MoveError::InvalidMove("The piece should not stay where it is");
+        }
+        if self
+            .board
+            .get_piece(destination)
+            .is_some_and(|other| other.color == piece.color)
+        {
+            return MoveError::SquareOccupied;
+        }
+
+        // A King moving two squares sideways castles
+        if matches!(piece.piece_type, PieceType::King(_))
+            && source.line == destination.line
+            && source.col.abs_diff(destination.col) == 2
+        {
+            return match self.find_castling_move(destination.col > source.col) {
+                Ok(castling) => self
+                    .validate_move(castling)
+                    .err()
+                    .unwrap_or(MoveError::InvalidCastle("The King cannot castle")),
+                Err(err) => err,
+            };
+        }
+
+        let next_move = self.build_move(source, destination);
+        let reachable = next_move.is_en_passant()
+            || piece
+                .get_possible_moves(&self.board, source)
+                .contains(&destination)
+            || self.board.attacks_from(&piece, source)
+                & self.board.pieces(self.turn.opposite())
+                & (1 << square(destination))
+                != 0;
+
+        match reachable {
+            true => self
+                .validate_move(next_move)
+                .err()
+                .unwrap_or(MoveError::InvalidMove("The piece cannot go to this square")),
+            false => MoveError::InvalidMove("The piece cannot go to this square"),
+        }
+    }
+}
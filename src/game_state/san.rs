@@ -0,0 +1,226 @@
+use super::GameState;
+use crate::errors::constants::{INTERNAL_ERROR_01, INTERNAL_ERROR_07};
+use crate::movement::{ChessPosition, Move};
+use crate::pgn::constants::{
+    CAPTURE, CHECK, CHECKMATE, KING_SIDE_CASTLING, PROMOTION, QUEEN_SIDE_CASTLING,
+};
+use crate::pieces::PieceType;
+use std::mem::discriminant;
+
+impl GameState {
+    /// Write a legal move of the side to move in Standard Algebraic Notation, with the minimal
+    /// disambiguation and the check or checkmate suffix
+    pub fn to_san(&self, next_move: Move) -> String {
+        let mut san = if next_move.is_castling() {
+            if next_move.destination().col > next_move.source().col {
+                KING_SIDE_CASTLING.to_owned()
+            }
+            else {
+                QUEEN_SIDE_CASTLING.to_owned()
+            }
+        }
+        else {
+            self.piece_move_to_san(next_move)
+        };
+
+        let mut game_state = self.clone();
+        game_state.apply_move(next_move);
+        if game_state.verify_checkmate() {
+            san.push(CHECKMATE);
+        }
+        else if game_state.is_check() {
+            san.push(CHECK);
+        }
+
+        san
+    }
+
+    fn piece_move_to_san(&self, next_move: Move) -> String {
+        let piece_type = self
+            .board
+            .get_piece(next_move.source())
+            .expect(INTERNAL_ERROR_07)
+            .piece_type;
+        let source: ChessPosition = next_move.source().try_into().expect(INTERNAL_ERROR_01);
+        let destination: ChessPosition =
+            next_move.destination().try_into().expect(INTERNAL_ERROR_01);
+        let is_capture =
+            next_move.is_en_passant() || self.board.is_position_occupied(next_move.destination());
+
+        let mut san = String::new();
+        match piece_type {
+            PieceType::Pawn(_) if is_capture => san.push(source.col),
+            PieceType::Pawn(_) => {}
+            _ => {
+                san.push(piece_type.letter());
+                san.push_str(&self.disambiguation(next_move, piece_type));
+            }
+        }
+
+        if is_capture {
+            san.push(CAPTURE);
+        }
+        san.push_str(&destination.to_string());
+
+        if let Some(promotion) = next_move.promotion {
+            san.push(PROMOTION);
+            san.push(promotion.letter());
+        }
+
+        san
+    }
+
+    /// Shortest prefix of the source square telling the moving piece apart from the other pieces
+    /// of the same type that can reach the destination: the file, then the rank, then both
+    fn disambiguation(&self, next_move: Move, piece_type: PieceType) -> String {
+        let source = next_move.source();
+        let rivals: Vec<_> = self
+            .legal_moves()
+            .into_iter()
+            .filter(|legal_move| {
+                legal_move.destination() == next_move.destination() && legal_move.source() != source
+            })
+            .map(|legal_move| legal_move.source())
+            .filter(|rival_source| {
+                self.board.get_piece(*rival_source).is_some_and(|rival| {
+                    discriminant(&rival.piece_type) == discriminant(&piece_type)
+                })
+            })
+            .collect();
+
+        let chess_source: ChessPosition = source.try_into().expect(INTERNAL_ERROR_01);
+        if rivals.is_empty() {
+            String::new()
+        }
+        else if rivals.iter().all(|rival| rival.col != source.col) {
+            chess_source.col.to_string()
+        }
+        else if rivals.iter().all(|rival| rival.line != source.line) {
+            chess_source.line.to_string()
+        }
+        else {
+            chess_source.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::movement::Position;
+    use crate::pgn::pgn_parser::parse_move;
+    use crate::utils::test_helper::{KIWIPETE, random_playouts, setup_game_state};
+
+    fn san_of(fen: &str, source: Position, destination: Position) -> String {
+        let game_state = GameState::from_fen(fen).unwrap();
+
+        game_state.to_san(game_state.build_move(source, destination))
+    }
+
+    #[test]
+    fn test_pawn_moves_and_captures() {
+        assert_eq!(
+            san_of(KIWIPETE, Position::new(6, 0), Position::new(4, 0)),
+            "a4"
+        );
+        assert_eq!(
+            san_of(KIWIPETE, Position::new(6, 6), Position::new(5, 7)),
+            "gxh3"
+        );
+        assert_eq!(
+            san_of(
+                "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1",
+                Position::new(3, 4),
+                Position::new(2, 3)
+            ),
+            "exd6"
+        );
+    }
+
+    #[test]
+    fn test_promotion_with_check() {
+        let game_state = GameState::from_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        let mut promotion = Move::new(Position::new(1, 1), Position::new(0, 1));
+        promotion.promotion = Some(PieceType::Queen);
+
+        assert_eq!(game_state.to_san(promotion), "b8=Q+");
+
+        promotion.promotion = Some(PieceType::Knight);
+
+        assert_eq!(game_state.to_san(promotion), "b8=N");
+    }
+
+    #[test]
+    fn test_castling() -> Result<(), crate::errors::MoveError> {
+        let game_state = GameState::from_fen(KIWIPETE).unwrap();
+
+        assert_eq!(
+            game_state.to_san(game_state.find_castling_move(true)?),
+            "O-O"
+        );
+        assert_eq!(
+            game_state.to_san(game_state.find_castling_move(false)?),
+            "O-O-O"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_disambiguation() {
+        let same_column = setup_game_state(Some("tests/validate_disambiguation_same_column.txt"));
+        let same_line = setup_game_state(Some("tests/validate_disambiguation_same_line.txt"));
+        let three_queens = "2k5/8/8/8/4Q2Q/8/8/K6Q w - - 0 1";
+
+        assert_eq!(
+            same_column.to_san(Move::new(Position::new(5, 1), Position::new(4, 3))),
+            "N3d4"
+        );
+        assert_eq!(
+            same_line.to_san(Move::new(Position::new(5, 2), Position::new(3, 3))),
+            "Ncd5"
+        );
+        assert_eq!(
+            san_of(KIWIPETE, Position::new(5, 2), Position::new(3, 1)),
+            "Nb5"
+        );
+        assert_eq!(
+            san_of(three_queens, Position::new(4, 7), Position::new(7, 4)),
+            "Qh4e1"
+        );
+        assert_eq!(
+            san_of(three_queens, Position::new(4, 4), Position::new(7, 4)),
+            "Qee1"
+        );
+        assert_eq!(
+            san_of(three_queens, Position::new(7, 7), Position::new(7, 4)),
+            "Q1e1"
+        );
+    }
+
+    #[test]
+    fn test_checkmate_suffix() {
+        assert_eq!(
+            GameState::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1")
+                .unwrap()
+                .to_san(Move::new(Position::new(7, 0), Position::new(0, 0))),
+            "Ra8#"
+        );
+    }
+
+    #[test]
+    fn test_parse_move_round_trip() {
+        random_playouts(0x2545_f491_4f6c_dd1d, 4, 40, |game_state, _| {
+            for legal_move in game_state.legal_moves() {
+                let san = game_state.to_san(legal_move);
+
+                assert_eq!(
+                    parse_move(game_state, &san),
+                    Ok(legal_move),
+                    "{san} in {}",
+                    game_state.to_fen()
+                );
+            }
+        });
+    }
+}
@@ -0,0 +1,126 @@
+use super::GameState;
+use crate::board::zobrist::{black_to_move_key, castling_key, en_passant_key};
+use crate::pieces::Color;
+
+/// Castling rights in the order of their Zobrist keys, as (color, is short castle)
+const CASTLING_RIGHTS: [(Color, bool); 4] = [
+    (Color::White, true),
+    (Color::White, false),
+    (Color::Black, true),
+    (Color::Black, false),
+];
+
+impl GameState {
+    /// Zobrist key identifying the position: the piece placement, the side to move, the castling
+    /// rights and the en passant file, the latter only when the capture is legal. Positions
+    /// equal under the repetition rules share the same key. The key is kept up to date as moves
+    /// are played and taken back.
+    pub fn zobrist_key(&self) -> u64 {
+        self.key
+    }
+
+    /// Zobrist key of the position computed from scratch, which [`GameState::zobrist_key`]
+    /// always matches
+    pub(crate) fn compute_full_key(&self) -> u64 {
+        let mut key = self.board.compute_key() ^ self.rights_key();
+
+        if self.turn == Color::Black {
+            key ^= black_to_move_key();
+        }
+
+        key
+    }
+
+    /// Keys of the castling rights and of the en passant file, the part of the key a move may
+    /// change besides the piece placement and the side to move
+    pub(super) fn rights_key(&self) -> u64 {
+        let mut key = 0;
+
+        for (right, (color, is_short_castle)) in CASTLING_RIGHTS.into_iter().enumerate() {
+            if self.has_castling_rights(color, is_short_castle) {
+                key ^= castling_key(right);
+            }
+        }
+
+        if let Some(col) = self.en_passant_file() {
+            key ^= en_passant_key(col);
+        }
+
+        key
+    }
+
+    /// Update the key once a move has been played or taken back, given the placement and rights
+    /// keys of the position before: those are XORed out, the new ones in, and the side to move
+    /// toggled
+    pub(super) fn update_key(&mut self, board_key: u64, rights_key: u64) {
+        self.key ^= board_key ^ self.board.key() ^ rights_key ^ self.rights_key();
+        self.key ^= black_to_move_key();
+    }
+
+    fn en_passant_file(&self) -> Option<usize> {
+        self.en_passant_moves()
+            .into_iter()
+            .find(|next_move| self.validate_move(*next_move).is_ok())
+            .map(|next_move| next_move.destination().col)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_helper::random_playouts;
+
+    #[test]
+    fn test_incremental_key_matches_key_from_scratch() {
+        let final_states = random_playouts(0xd1b5_4a32_d192_ed03, 8, 80, |game_state, _| {
+            assert_eq!(
+                game_state.zobrist_key(),
+                game_state.compute_full_key(),
+                "{}",
+                game_state.to_fen()
+            );
+        });
+
+        // Taking moves back keeps the key up to date as well
+        for mut game_state in final_states {
+            loop {
+                assert_eq!(
+                    game_state.zobrist_key(),
+                    game_state.compute_full_key(),
+                    "{}",
+                    game_state.to_fen()
+                );
+                if game_state.undo().is_none() {
+                    break;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_key_covers_side_castling_and_en_passant() {
+        let key = |fen: &str| GameState::from_fen(fen).unwrap().zobrist_key();
+
+        let position = "r3k2r/8/8/8/3pP3/8/8/R3K2R b KQkq e3 0 1";
+        assert_ne!(
+            key("r3k2r/8/8/8/3pP3/8/8/R3K2R b KQkq - 0 1"),
+            key("r3k2r/8/8/8/3pP3/8/8/R3K2R w KQkq - 0 1")
+        );
+        assert_ne!(
+            key(position),
+            key("r3k2r/8/8/8/3pP3/8/8/R3K2R b Kkq e3 0 1")
+        );
+        assert_ne!(
+            key(position),
+            key("r3k2r/8/8/8/3pP3/8/8/R3K2R b KQkq - 0 1")
+        );
+        assert_eq!(
+            key("r3k2r/8/8/8/4P3/8/8/R3K2R b KQkq e3 0 1"),
+            key("r3k2r/8/8/8/4P3/8/8/R3K2R b KQkq - 0 1")
+        );
+        assert_eq!(
+            key(position),
+            key("r3k2r/8/8/8/3pP3/8/8/R3K2R b KQkq e3 40 90")
+        );
+    }
+}
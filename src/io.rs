@@ -1,2 +1,3 @@
 pub mod file_manager;
+pub mod positions;
 pub mod ui;
@@ -1,15 +1,7 @@
 use std::fs::File;
-use std::io::{BufRead, BufReader, Lines};
+use std::io::{self, BufReader};
+use std::path::Path;
 
-const POSITIONS_DIRECTORY: &str = "res/positions/";
-const DEFAULT_POSITIONS_FILE: &str = "initial_positions.txt";
-
-pub fn initial_positions(positions_file: Option<&str>) -> Lines<BufReader<File>> {
-    let positions_file = positions_file.unwrap_or(DEFAULT_POSITIONS_FILE);
-    let positions_file_path = format!("{POSITIONS_DIRECTORY}/{positions_file}");
-
-    let file = File::open(&positions_file_path)
-        .unwrap_or_else(|_| panic!("Could not open file {}", &positions_file_path));
-
-    BufReader::new(file).lines()
+pub fn open_file(path: &Path) -> io::Result<BufReader<File>> {
+    Ok(BufReader::new(File::open(path)?))
 }
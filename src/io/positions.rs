@@ -0,0 +1,180 @@
+use crate::Board;
+use crate::errors::{SetupError, SetupErrorKind};
+use crate::movement::{ChessPosition, Position};
+use crate::pieces::types::Pawn;
+use crate::pieces::{Color, Piece, PieceType};
+use std::io::{self, BufRead};
+
+/// Standard starting position, built into the library so that it does not depend on the working
+/// directory
+pub const DEFAULT_POSITIONS: &str = include_str!("../../res/positions/initial_positions.txt");
+
+/// Build a board from a reader yielding one piece per line, as described by [`parse_positions`]
+pub fn read_positions(reader: impl BufRead) -> Result<Board, SetupError> {
+    parse_positions(reader.lines())
+}
+
+/// Build a board from lines describing one piece each, as a color (`W` or `B`), a piece letter
+/// and a square (e.g., `WKe1`)
+pub fn parse_positions(
+    lines: impl Iterator<Item = io::Result<String>>,
+) -> Result<Board, SetupError> {
+    let mut board = Board::new();
+
+    for (index, wrapped_line) in lines.enumerate() {
+        let line = wrapped_line?;
+        let (piece, position) =
+            parse_line(&line).map_err(|kind| SetupError::InvalidLine(index + 1, kind))?;
+
+        if board.is_position_occupied(position) {
+            return Err(SetupError::InvalidLine(
+                index + 1,
+                SetupErrorKind::DuplicateSquare,
+            ));
+        }
+
+        board.add_piece(piece, position);
+    }
+
+    Ok(board)
+}
+
+fn parse_line(line: &str) -> Result<(Piece, Position), SetupErrorKind> {
+    let mut chars = line.chars();
+    let (Some(color_char), Some(piece_char), Some(chess_col), Some(chess_line)) =
+        (chars.next(), chars.next(), chars.next(), chars.next())
+    else {
+        return Err(SetupErrorKind::IncompleteLine);
+    };
+
+    let rest = chars.as_str().trim_end();
+    if !rest.is_empty() {
+        return Err(SetupErrorKind::TrailingCharacters(rest.to_string()));
+    }
+
+    let piece_color: Color = color_char
+        .try_into()
+        .map_err(|_| SetupErrorKind::InvalidColor(color_char))?;
+
+    let piece_type = PieceType::try_from(piece_char)
+        .ok()
+        .filter(|piece_type| {
+            piece_char.is_ascii_uppercase() && !matches!(piece_type, PieceType::None)
+        })
+        .ok_or(SetupErrorKind::InvalidPiece(piece_char))?;
+
+    let piece_position: Position = ChessPosition::new(chess_line, chess_col)
+        .try_into()
+        .map_err(SetupErrorKind::InvalidSquare)?;
+
+    if matches!(piece_type, PieceType::Pawn(_)) && Pawn::is_back_rank(piece_position) {
+        return Err(SetupErrorKind::PawnOnBackRank);
+    }
+
+    Ok((Piece::new(piece_type, piece_color), piece_position))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GameState;
+    use crate::errors::ChessPositionError;
+    use crate::io::file_manager::open_file;
+    use crate::utils::test_helper::fixture_path;
+
+    fn parse_fixture(positions_file: &str) -> Result<Board, SetupError> {
+        read_positions(open_file(&fixture_path(positions_file))?)
+    }
+
+    #[test]
+    fn test_read_default_positions() -> Result<(), SetupError> {
+        let board = read_positions(DEFAULT_POSITIONS.as_bytes())?;
+
+        assert_eq!(board.into_iter().count(), 32);
+        assert_eq!(board, parse_fixture("initial_positions.txt")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_positions_from_str() -> Result<(), SetupError> {
+        let board = read_positions("WKe1\nBKe8\nWPe2\n".as_bytes())?;
+
+        assert_eq!(board.into_iter().count(), 3);
+        assert!(board.is_position_occupied(Position::new(6, 4)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_positions_errors() {
+        let cases = [
+            (
+                "tests/setup/incomplete_line.txt",
+                2,
+                SetupErrorKind::IncompleteLine,
+            ),
+            (
+                "tests/setup/invalid_color.txt",
+                2,
+                SetupErrorKind::InvalidColor('X'),
+            ),
+            (
+                "tests/setup/invalid_piece.txt",
+                3,
+                SetupErrorKind::InvalidPiece('Z'),
+            ),
+            (
+                "tests/setup/invalid_square.txt",
+                2,
+                SetupErrorKind::InvalidSquare(ChessPositionError::InvalidColumn('i')),
+            ),
+            (
+                "tests/setup/duplicate_square.txt",
+                2,
+                SetupErrorKind::DuplicateSquare,
+            ),
+            (
+                "tests/setup/pawn_on_back_rank.txt",
+                3,
+                SetupErrorKind::PawnOnBackRank,
+            ),
+            (
+                "tests/setup/trailing_characters.txt",
+                2,
+                SetupErrorKind::TrailingCharacters(String::from("garbage")),
+            ),
+        ];
+
+        for (positions_file, line, kind) in cases {
+            assert_eq!(
+                parse_fixture(positions_file),
+                Err(SetupError::InvalidLine(line, kind)),
+                "{positions_file}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_positions_missing_file() {
+        assert_eq!(
+            parse_fixture("tests/setup/missing.txt"),
+            Err(SetupError::Io {
+                path: None,
+                kind: io::ErrorKind::NotFound
+            })
+        );
+
+        let path = fixture_path("tests/setup/missing.txt");
+        let err = GameState::from_positions_file(&path).unwrap_err();
+
+        assert_eq!(
+            err,
+            SetupError::Io {
+                path: Some(path.clone()),
+                kind: io::ErrorKind::NotFound
+            }
+        );
+        assert!(err.to_string().contains("missing.txt"));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::GameState;
+use crate::pgn::PgnTags;
 use std::io::{self, Write, stdin, stdout};
 
 pub fn read_move() -> io::Result<String> {
@@ -30,3 +31,8 @@ pub fn print_game(game_state: &GameState) {
 
     println!();
 }
+
+pub fn print_pgn(game_state: &GameState) {
+    println!();
+    print!("{}", game_state.to_pgn(&PgnTags::default()));
+}
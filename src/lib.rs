@@ -1,37 +1,73 @@
 mod board;
+pub mod engine;
 pub mod errors;
+pub mod eval;
 mod game_state;
 mod io;
 pub mod movement;
-mod pgn;
-mod pieces;
+pub mod pgn;
+pub mod pieces;
+mod protocol;
 mod utils;
 
+use crate::engine::{Engine, SearchLimits};
+use crate::errors::constants::INTERNAL_ERROR_09;
 use crate::io::ui;
+use crate::movement::AnyNotation;
 pub use board::Board;
-pub use game_state::GameState;
-use std::process::exit;
+pub use game_state::{GameOutcome, GameState, PlayedMove};
+use std::time::{Duration, Instant};
 
-pub fn run(game_state: &mut GameState) {
+const UNDO_COMMAND: &str = "undo";
+const REDO_COMMAND: &str = "redo";
+const DRAW_COMMAND: &str = "draw";
+const RESIGN_COMMAND: &str = "resign";
+const ENGINE_COMMAND: &str = "go";
+const EVAL_COMMAND: &str = "eval";
+
+/// Time the engine thinks for when asked to play a move
+const ENGINE_THINKING_TIME: Duration = Duration::from_secs(1);
+
+/// Play a game in the terminal until it ends, and return how it ended
+pub fn run(game_state: &mut GameState) -> GameOutcome {
+    // Kept for the whole game, so that the transposition table serves every move
+    let mut engine = Engine::new();
     ui::print_game(game_state);
 
     loop {
         match ui::read_move() {
+            Ok(command) if command == UNDO_COMMAND => {
+                game_state.undo();
+            }
+            Ok(command) if command == REDO_COMMAND => {
+                game_state.redo();
+            }
+            Ok(command) if command == DRAW_COMMAND => {
+                if !game_state.claim_draw() {
+                    ui::print_game(game_state);
+                    println!("No draw can be claimed");
+                    continue;
+                }
+            }
+            Ok(command) if command == RESIGN_COMMAND => game_state.resign(),
+            Ok(command) if command == ENGINE_COMMAND => {
+                let result = engine.search(game_state, SearchLimits::time(ENGINE_THINKING_TIME));
+                if let Some(best_move) = result.best_move {
+                    game_state.play_move(best_move).expect(INTERNAL_ERROR_09);
+                }
+            }
+            Ok(command) if command == EVAL_COMMAND => {
+                ui::print_game(game_state);
+                println!("{}", eval::evaluate_terms(game_state));
+                continue;
+            }
             Ok(next_move) => {
-                if let Err(move_err) = game_state.handle_move(next_move.as_str()) {
+                if let Err(move_err) = game_state.handle_move_as(next_move.as_str(), &AnyNotation) {
                     ui::print_game(game_state);
                     println!("{next_move}");
                     println!("{move_err}");
                     continue;
                 }
-                if game_state.verify_checkmate() {
-                    println!("Checkmate!");
-                    exit(0)
-                }
-                if game_state.is_stalemate() {
-                    println!("Stalemate!");
-                    exit(0);
-                }
             }
             Err(move_err) => {
                 ui::print_game(game_state);
@@ -41,5 +77,76 @@ pub fn run(game_state: &mut GameState) {
         }
 
         ui::print_game(game_state);
+
+        if let Some(outcome) = game_state.outcome() {
+            println!("{outcome}");
+            ui::print_pgn(game_state);
+
+            return outcome;
+        }
     }
 }
+
+/// Print the perft count of every legal move down to the given depth, then the total count and
+/// the time it took
+pub fn run_perft(game_state: &GameState, depth: usize) -> u64 {
+    let start = Instant::now();
+
+    let (moves, nodes) = game_state.divide(depth);
+    for (next_move, move_nodes) in moves {
+        println!("{next_move}: {move_nodes}");
+    }
+
+    let elapsed = start.elapsed();
+    println!();
+    println!("Nodes searched: {nodes}");
+    println!(
+        "Time: {} ms ({:.0} nodes/s)",
+        elapsed.as_millis(),
+        nodes as f64 / elapsed.as_secs_f64()
+    );
+
+    nodes
+}
+
+/// Search the position to the given depth with and without a transposition table, print the
+/// nodes and time each search took, and return both node counts
+pub fn run_bench(game_state: &GameState, depth: usize) -> (u64, u64) {
+    let with_table = bench_search(game_state, depth, engine::DEFAULT_SIZE_MB);
+    let without_table = bench_search(game_state, depth, 0);
+
+    println!("With transposition table:    {with_table} nodes");
+    println!("Without transposition table: {without_table} nodes");
+
+    (with_table, without_table)
+}
+
+fn bench_search(game_state: &GameState, depth: usize, hash_size_mb: usize) -> u64 {
+    let start = Instant::now();
+    let result =
+        Engine::with_hash_size(hash_size_mb).search(game_state, SearchLimits::depth(depth));
+    let elapsed = start.elapsed();
+
+    println!(
+        "Hash {hash_size_mb} MB: best move {}, score {}, {} nodes in {} ms ({:.0} nodes/s)",
+        result
+            .best_move
+            .map_or(String::from("none"), |best_move| best_move.to_string()),
+        result.score,
+        result.nodes,
+        elapsed.as_millis(),
+        result.nodes as f64 / elapsed.as_secs_f64()
+    );
+
+    result.nodes
+}
+
+/// Speak the Universal Chess Interface on the standard input and output, until `quit`
+pub fn run_uci() {
+    protocol::uci::Uci::new(std::io::stdout()).run(std::io::stdin().lock());
+}
+
+/// Speak the Chess Engine Communication Protocol on the standard input and output, until `quit`
+pub fn run_xboard() {
+    protocol::xboard::Xboard::new(std::io::stdout()).run(std::io::stdin().lock());
+}
@@ -1,8 +1,73 @@
-use chess::{GameState, run};
+use chess::{GameState, run, run_bench, run_perft, run_uci, run_xboard};
+use std::env;
+use std::path::PathBuf;
+use std::process;
+
+const PERFT_COMMAND: &str = "perft";
+const PERFT_USAGE: &str = "Usage: chess perft <depth> [fen]";
+const UCI_FLAG: &str = "--uci";
+const XBOARD_FLAG: &str = "--xboard";
+const BENCH_COMMAND: &str = "bench";
+const BENCH_USAGE: &str = "Usage: chess bench <depth> [fen]";
 
 fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    if args.first().is_some_and(|arg| arg == UCI_FLAG) {
+        run_uci();
+        return;
+    }
+    if args.first().is_some_and(|arg| arg == XBOARD_FLAG) {
+        run_xboard();
+        return;
+    }
+    if args.first().is_some_and(|arg| arg == PERFT_COMMAND) {
+        perft(&args[1..]);
+        return;
+    }
+    if args.first().is_some_and(|arg| arg == BENCH_COMMAND) {
+        bench(&args[1..]);
+        return;
+    }
+
+    let positions_file = args.first().map(PathBuf::from);
+
     let mut game_state = GameState::new();
-    game_state.initialize(None);
+    if let Err(err) = game_state.initialize(positions_file.as_deref()) {
+        eprintln!("{err}");
+        process::exit(1);
+    }
 
     run(&mut game_state);
 }
+
+fn perft(args: &[String]) {
+    let (depth, game_state) = depth_and_position(args, PERFT_USAGE);
+
+    run_perft(&game_state, depth);
+}
+
+fn bench(args: &[String]) {
+    let (depth, game_state) = depth_and_position(args, BENCH_USAGE);
+
+    run_bench(&game_state, depth);
+}
+
+/// Parse the `<depth> [fen]` arguments, the position defaulting to the starting one
+fn depth_and_position(args: &[String], usage: &str) -> (usize, GameState) {
+    let Some(depth) = args.first().and_then(|depth| depth.parse().ok())
+    else {
+        eprintln!("{usage}");
+        process::exit(1);
+    };
+
+    let game_state = match args.get(1) {
+        Some(fen) => GameState::from_fen(fen).unwrap_or_else(|err| {
+            eprintln!("{err}");
+            process::exit(1);
+        }),
+        None => GameState::starting_position(),
+    };
+
+    (depth, game_state)
+}
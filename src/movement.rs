@@ -1,9 +1,14 @@
+mod annotation;
 mod direction;
 mod r#move;
+mod notation;
 mod position;
 
+pub use annotation::Annotation;
 pub use direction::Direction;
 pub use r#move::Move;
+pub use notation::{AnyNotation, Coordinate, LongAlgebraic, MoveNotation, San};
+pub(crate) use notation::{is_coordinate_move, parse_coordinate_move};
 pub use position::ChessPosition;
 pub use position::Position;
 pub use position::PositionI8;
@@ -0,0 +1,85 @@
+use crate::errors::PgnError;
+use crate::pgn::constants::ANNOTATION_CHARS;
+use std::fmt::{Display, Formatter};
+
+/// Move assessment glyphs that may follow a PGN move
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Annotation {
+    Good,
+    Mistake,
+    Brilliant,
+    Blunder,
+    Interesting,
+    Dubious,
+}
+
+impl TryFrom<&str> for Annotation {
+    type Error = PgnError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "!" => Ok(Annotation::Good),
+            "?" => Ok(Annotation::Mistake),
+            "!!" => Ok(Annotation::Brilliant),
+            "??" => Ok(Annotation::Blunder),
+            "!?" => Ok(Annotation::Interesting),
+            "?!" => Ok(Annotation::Dubious),
+            _ => {
+                // Report the first character that cannot be part of an annotation
+                let invalid = value
+                    .chars()
+                    .enumerate()
+                    .find(|(i, c)| *i > 1 || !ANNOTATION_CHARS.contains(c))
+                    .map(|(_, c)| c)
+                    .unwrap_or_default();
+
+                Err(PgnError::InvalidCharacter(invalid))
+            }
+        }
+    }
+}
+
+impl Display for Annotation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Good => write!(f, "!"),
+            Self::Mistake => write!(f, "?"),
+            Self::Brilliant => write!(f, "!!"),
+            Self::Blunder => write!(f, "??"),
+            Self::Interesting => write!(f, "!?"),
+            Self::Dubious => write!(f, "?!"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_annotation_round_trip() -> Result<(), PgnError> {
+        for symbol in ["!", "?", "!!", "??", "!?", "?!"] {
+            let annotation = Annotation::try_from(symbol)?;
+
+            assert_eq!(annotation.to_string(), symbol);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_annotation() {
+        assert_eq!(
+            Annotation::try_from("abc"),
+            Err(PgnError::InvalidCharacter('a'))
+        );
+        assert_eq!(
+            Annotation::try_from("!x"),
+            Err(PgnError::InvalidCharacter('x'))
+        );
+        assert_eq!(
+            Annotation::try_from("!!!"),
+            Err(PgnError::InvalidCharacter('!'))
+        );
+    }
+}
@@ -1,17 +1,26 @@
-use crate::movement::Position;
+use crate::errors::constants::INTERNAL_ERROR_01;
+use crate::movement::{Annotation, ChessPosition, Position};
+use crate::pieces::PieceType;
+use std::fmt::Display;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 /// Represents a single piece move
 pub struct PieceMove {
     pub source: Position,
     pub destination: Position,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 /// Represents a complete move, potentially including an additional move (e.g., for castling)
 pub struct Move {
     pub primary: PieceMove,
     pub additional: Option<PieceMove>,
+    /// Position of the pawn captured en passant, which differs from the destination square
+    pub en_passant: Option<Position>,
+    /// Piece type a pawn is promoted to when reaching the last rank
+    pub promotion: Option<PieceType>,
+    /// Assessment glyph written after the move (e.g., `!?`), which has no effect on the game
+    pub annotation: Option<Annotation>,
 }
 
 impl Move {
@@ -22,6 +31,9 @@ impl Move {
                 destination,
             },
             additional: None,
+            en_passant: None,
+            promotion: None,
+            annotation: None,
         }
     }
 
@@ -40,6 +52,22 @@ impl Move {
                 source: additional_source,
                 destination: additional_destination,
             }),
+            en_passant: None,
+            promotion: None,
+            annotation: None,
+        }
+    }
+
+    pub fn with_en_passant(source: Position, destination: Position) -> Self {
+        Self {
+            primary: PieceMove {
+                source,
+                destination,
+            },
+            additional: None,
+            en_passant: Some(Position::new(source.line, destination.col)),
+            promotion: None,
+            annotation: None,
         }
     }
 
@@ -54,4 +82,34 @@ impl Move {
     pub fn is_castling(&self) -> bool {
         self.additional.is_some()
     }
+
+    pub fn is_promotion(&self) -> bool {
+        self.promotion.is_some()
+    }
+
+    pub fn is_en_passant(&self) -> bool {
+        self.en_passant.is_some()
+    }
+
+    /// Return the square a captured piece would stand on: the destination square, unless it is an
+    /// en passant capture
+    pub fn capture_position(&self) -> Position {
+        self.en_passant.unwrap_or(self.destination())
+    }
+}
+
+/// Coordinate notation of the move (e.g., `e2e4`, `e1g1` for castling or `e7e8q` for a
+/// promotion), which unlike SAN does not depend on the position
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let source: ChessPosition = self.source().try_into().expect(INTERNAL_ERROR_01);
+        let destination: ChessPosition = self.destination().try_into().expect(INTERNAL_ERROR_01);
+
+        write!(f, "{source}{destination}")?;
+        if let Some(promotion) = self.promotion {
+            write!(f, "{}", promotion.letter().to_ascii_lowercase())?;
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,421 @@
+//! Text notations of moves, each parsed into the [`Move`] of the side to move it stands for, with
+//! the same [`MoveError`] diagnostics whatever the notation
+
+use crate::GameState;
+use crate::errors::{MoveError, PgnError};
+use crate::movement::{ChessPosition, Move, Position};
+use crate::pgn::constants::{CAPTURE, CHECK, CHECKMATE, KING_SIDE_CASTLING, PROMOTION};
+use crate::pgn::pgn_parser;
+use crate::pieces::PieceType;
+use std::mem::discriminant;
+use std::str::Chars;
+
+const LONG_ALGEBRAIC_MOVE: char = '-';
+
+/// Parser of one notation, chosen by the caller of [`GameState::handle_move_as`]
+pub trait MoveNotation {
+    fn parse_move(&self, game_state: &GameState, text: &str) -> Result<Move, MoveError>;
+}
+
+/// Standard Algebraic Notation, naming the piece and its destination (e.g., `e4`, `Nf3`,
+/// `exd5`, `O-O` or `e8=Q+`)
+pub struct San;
+
+/// Coordinate notation used by UCI, naming the source and destination squares and the
+/// lowercase promotion piece (e.g., `e2e4`, `e1g1` for castling or `e7e8q`)
+pub struct Coordinate;
+
+/// Long algebraic notation, naming the piece, its source and its destination (e.g., `e2-e4`,
+/// `Ng1-f3`, `e4xd5`, `O-O` or `e7-e8=Q+`)
+pub struct LongAlgebraic;
+
+/// Any of the other notations, told apart by the shape of the text, for input typed by users
+pub struct AnyNotation;
+
+impl MoveNotation for San {
+    fn parse_move(&self, game_state: &GameState, text: &str) -> Result<Move, MoveError> {
+        pgn_parser::parse_move(game_state, text)
+    }
+}
+
+impl MoveNotation for Coordinate {
+    fn parse_move(&self, game_state: &GameState, text: &str) -> Result<Move, MoveError> {
+        parse_coordinate_move(game_state, text)
+    }
+}
+
+impl MoveNotation for LongAlgebraic {
+    fn parse_move(&self, game_state: &GameState, text: &str) -> Result<Move, MoveError> {
+        // Castling is written as in SAN
+        if text.starts_with(KING_SIDE_CASTLING) {
+            return San.parse_move(game_state, text);
+        }
+        if text.is_empty() {
+            return Err(PgnError::EmptyInput.into());
+        }
+
+        let mut chars = text.chars();
+        let piece_type = match text.chars().next() {
+            Some(letter @ ('K' | 'Q' | 'R' | 'B' | 'N' | 'P')) => {
+                chars.next();
+                PieceType::try_from(letter)?
+            }
+            Some(letter) if letter.is_ascii_uppercase() => {
+                return Err(PgnError::InvalidPiece(letter).into());
+            }
+            _ => PieceType::Pawn(Default::default()),
+        };
+
+        let source = next_square(&mut chars, "source square")?;
+        let capture = match chars.next() {
+            Some(LONG_ALGEBRAIC_MOVE) => false,
+            Some(CAPTURE) => true,
+            Some(other) => return Err(PgnError::InvalidCharacter(other).into()),
+            None => return Err(PgnError::MissingCharacter("move or capture").into()),
+        };
+        let destination = next_square(&mut chars, "destination square")?;
+
+        let mut suffix = chars.as_str();
+        let mut promotion = None;
+        if let Some(rest) = suffix.strip_prefix(PROMOTION) {
+            let mut rest = rest.chars();
+            let letter = rest.next().ok_or(PgnError::MissingCharacter("promotion"))?;
+            promotion = Some(PieceType::try_from(letter)?);
+            suffix = rest.as_str();
+        }
+        let check = suffix.starts_with(CHECK);
+        let checkmate = suffix.starts_with(CHECKMATE);
+        if check || checkmate {
+            suffix = &suffix[1..];
+        }
+
+        let piece = game_state
+            .get_piece(source)
+            .filter(|piece| discriminant(&piece.piece_type) == discriminant(&piece_type))
+            .ok_or(MoveError::NoPieceAvailable)?;
+        let is_capture = game_state
+            .get_piece(
+                game_state
+                    .build_move(source, destination)
+                    .capture_position(),
+            )
+            .is_some_and(|captured| captured.color != piece.color);
+        match (capture, is_capture) {
+            (true, false) => {
+                return Err(MoveError::InvalidCapture("Destination square is empty"));
+            }
+            (false, true) => return Err(PgnError::MissingCaptureCharacter.into()),
+            _ => {}
+        }
+
+        let mut next_move = game_state.resolve_move(source, destination, promotion)?;
+        if !suffix.is_empty() {
+            next_move.annotation = Some(suffix.try_into()?);
+        }
+        if check || checkmate {
+            game_state.validate_check(next_move, checkmate)?;
+        }
+
+        Ok(next_move)
+    }
+}
+
+impl MoveNotation for AnyNotation {
+    fn parse_move(&self, game_state: &GameState, text: &str) -> Result<Move, MoveError> {
+        let notation: &dyn MoveNotation = if is_coordinate_move(text) {
+            &Coordinate
+        }
+        else if is_long_algebraic_move(text) {
+            &LongAlgebraic
+        }
+        else {
+            &San
+        };
+
+        notation.parse_move(game_state, text)
+    }
+}
+
+/// Parse a move of the side to move in coordinate notation, as exchanged with UCI GUIs: the
+/// source and destination squares, then the lowercase promotion piece if any (e.g., `e2e4`,
+/// `e1g1` for castling or `e7e8q`)
+pub(crate) fn parse_coordinate_move(game_state: &GameState, text: &str) -> Result<Move, MoveError> {
+    if text.is_empty() {
+        return Err(PgnError::EmptyInput.into());
+    }
+
+    let mut chars = text.chars();
+    let source = next_square(&mut chars, "source square")?;
+    let destination = next_square(&mut chars, "destination square")?;
+    let promotion = chars
+        .next()
+        .map(|letter| PieceType::try_from(letter.to_ascii_uppercase()))
+        .transpose()?;
+    if let Some(extra) = chars.next() {
+        return Err(PgnError::InvalidCharacter(extra).into());
+    }
+
+    game_state.resolve_move(source, destination, promotion)
+}
+
+/// Whether the text has the shape of a move in coordinate notation, legal or not
+pub(crate) fn is_coordinate_move(text: &str) -> bool {
+    let bytes = text.as_bytes();
+
+    (4..=5).contains(&bytes.len()) && is_square(&bytes[0..2]) && is_square(&bytes[2..4])
+}
+
+/// Whether the text starts like a move in long algebraic notation: the optional piece, the
+/// source square and the move or capture character
+fn is_long_algebraic_move(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    let start = match bytes.first() {
+        Some(letter) if letter.is_ascii_uppercase() => 1,
+        _ => 0,
+    };
+
+    bytes.len() >= start + 3
+        && is_square(&bytes[start..start + 2])
+        && [LONG_ALGEBRAIC_MOVE as u8, CAPTURE as u8].contains(&bytes[start + 2])
+}
+
+fn is_square(bytes: &[u8]) -> bool {
+    (b'a'..=b'h').contains(&bytes[0]) && (b'1'..=b'8').contains(&bytes[1])
+}
+
+/// Read a square from its column and line characters
+fn next_square(chars: &mut Chars, step: &'static str) -> Result<Position, MoveError> {
+    let col = chars.next().ok_or(PgnError::MissingCharacter(step))?;
+    let line = chars.next().ok_or(PgnError::MissingCharacter(step))?;
+
+    Ok(ChessPosition::new(line, col).try_into()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::ChessPositionError;
+    use crate::movement::Annotation;
+
+    const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+    fn parse_coordinate(fen: &str, text: &str) -> Result<String, MoveError> {
+        let game_state = GameState::from_fen(fen).unwrap();
+
+        parse_coordinate_move(&game_state, text).map(|next_move| game_state.to_san(next_move))
+    }
+
+    fn parse(notation: &dyn MoveNotation, fen: &str, text: &str) -> Result<String, MoveError> {
+        let game_state = GameState::from_fen(fen).unwrap();
+
+        notation
+            .parse_move(&game_state, text)
+            .map(|next_move| game_state.to_san(next_move))
+    }
+
+    #[test]
+    fn test_notations_resolve_to_the_same_move() {
+        let game_state = GameState::from_fen(START).unwrap();
+        let expected = San.parse_move(&game_state, "Nf3").unwrap();
+
+        assert_eq!(Coordinate.parse_move(&game_state, "g1f3"), Ok(expected));
+        assert_eq!(
+            LongAlgebraic.parse_move(&game_state, "Ng1-f3"),
+            Ok(expected)
+        );
+        for text in ["Nf3", "g1f3", "Ng1-f3"] {
+            assert_eq!(AnyNotation.parse_move(&game_state, text), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn test_coordinate_moves() {
+        assert_eq!(parse_coordinate(START, "e2e4"), Ok(String::from("e4")));
+        assert_eq!(
+            parse_coordinate("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1", "e8c8"),
+            Ok(String::from("O-O-O"))
+        );
+        assert_eq!(
+            parse_coordinate("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6"),
+            Ok(String::from("exd6"))
+        );
+        assert_eq!(
+            parse_coordinate("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1", "b7b8n"),
+            Ok(String::from("b8=N"))
+        );
+        assert_eq!(
+            parse_coordinate("4k3/8/8/8/8/8/4r3/4K3 w - - 0 1", "e1e2"),
+            Ok(String::from("Kxe2"))
+        );
+    }
+
+    #[test]
+    fn test_invalid_coordinate_moves() {
+        assert_eq!(
+            parse_coordinate(START, ""),
+            Err(PgnError::EmptyInput.into())
+        );
+        assert_eq!(
+            parse_coordinate(START, "e2"),
+            Err(PgnError::MissingCharacter("destination square").into())
+        );
+        assert_eq!(
+            parse_coordinate(START, "e2e9"),
+            Err(PgnError::InvalidChessPosition(ChessPositionError::InvalidLine('9')).into())
+        );
+        assert_eq!(
+            parse_coordinate(START, "e2e4q!"),
+            Err(PgnError::InvalidCharacter('!').into())
+        );
+        assert_eq!(
+            parse_coordinate(START, "e3e4"),
+            Err(MoveError::NoPieceAvailable)
+        );
+        assert_eq!(
+            parse_coordinate(START, "e7e5"),
+            Err(MoveError::NoPieceAvailable)
+        );
+        assert_eq!(
+            parse_coordinate(START, "d1d2"),
+            Err(MoveError::SquareOccupied)
+        );
+        assert!(matches!(
+            parse_coordinate(START, "e2e5"),
+            Err(MoveError::InvalidMove(_))
+        ));
+        assert!(matches!(
+            parse_coordinate(
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKB1R w KQkq - 0 1",
+                "e1g1"
+            ),
+            Err(MoveError::InvalidCastle(_))
+        ));
+        assert_eq!(
+            parse_coordinate("4k3/4r3/8/8/8/8/4B3/4K3 w - - 0 1", "e2d3"),
+            Err(MoveError::KingWouldBeInCheck)
+        );
+    }
+
+    #[test]
+    fn test_invalid_coordinate_promotions() {
+        let fen = "4k3/1P6/8/8/8/8/8/4K3 w - - 0 1";
+
+        assert_eq!(
+            parse_coordinate(fen, "b7b8"),
+            Err(MoveError::MissingPromotion)
+        );
+        assert!(matches!(
+            parse_coordinate(fen, "b7b8k"),
+            Err(MoveError::InvalidPromotion(_))
+        ));
+        assert!(matches!(
+            parse_coordinate(START, "e2e4q"),
+            Err(MoveError::InvalidPromotion(_))
+        ));
+    }
+
+    #[test]
+    fn test_long_algebraic_moves() {
+        assert_eq!(
+            parse(&LongAlgebraic, START, "e2-e4"),
+            Ok(String::from("e4"))
+        );
+        assert_eq!(
+            parse(&LongAlgebraic, START, "Pe2-e4"),
+            Ok(String::from("e4"))
+        );
+        assert_eq!(
+            parse(&LongAlgebraic, "4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1", "e4xd5"),
+            Ok(String::from("exd5"))
+        );
+        assert_eq!(
+            parse(&LongAlgebraic, "4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5xd6"),
+            Ok(String::from("exd6"))
+        );
+        assert_eq!(
+            parse(
+                &LongAlgebraic,
+                "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1",
+                "O-O-O"
+            ),
+            Ok(String::from("O-O-O"))
+        );
+        assert_eq!(
+            parse(&LongAlgebraic, "7k/1P6/8/8/8/8/8/4K3 w - - 0 1", "b7-b8=Q+"),
+            Ok(String::from("b8=Q+"))
+        );
+        assert_eq!(
+            parse(
+                &LongAlgebraic,
+                "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1",
+                "Ra1-a8#"
+            ),
+            Ok(String::from("Ra8#"))
+        );
+
+        let game_state = GameState::from_fen(START).unwrap();
+        assert_eq!(
+            LongAlgebraic
+                .parse_move(&game_state, "Ng1-f3!?")
+                .unwrap()
+                .annotation,
+            Some(Annotation::Interesting)
+        );
+    }
+
+    #[test]
+    fn test_invalid_long_algebraic_moves() {
+        assert_eq!(
+            parse(&LongAlgebraic, START, ""),
+            Err(PgnError::EmptyInput.into())
+        );
+        assert_eq!(
+            parse(&LongAlgebraic, START, "Xg1-f3"),
+            Err(PgnError::InvalidPiece('X').into())
+        );
+        assert_eq!(
+            parse(&LongAlgebraic, START, "Ng1"),
+            Err(PgnError::MissingCharacter("move or capture").into())
+        );
+        assert_eq!(
+            parse(&LongAlgebraic, START, "Ng1/f3"),
+            Err(PgnError::InvalidCharacter('/').into())
+        );
+        // The piece on the source square is a Knight
+        assert_eq!(
+            parse(&LongAlgebraic, START, "Bg1-f3"),
+            Err(MoveError::NoPieceAvailable)
+        );
+        assert_eq!(
+            parse(&LongAlgebraic, START, "e2xe4"),
+            Err(MoveError::InvalidCapture("Destination square is empty"))
+        );
+        assert_eq!(
+            parse(&LongAlgebraic, "4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1", "e4-d5"),
+            Err(PgnError::MissingCaptureCharacter.into())
+        );
+        assert_eq!(
+            parse(&LongAlgebraic, START, "Ng1-f3+"),
+            Err(MoveError::InvalidCheck("This move does not give check"))
+        );
+        assert_eq!(
+            parse(&LongAlgebraic, "4k3/1P6/8/8/8/8/8/4K3 w - - 0 1", "b7-b8"),
+            Err(MoveError::MissingPromotion)
+        );
+    }
+
+    #[test]
+    fn test_any_notation() {
+        assert_eq!(parse(&AnyNotation, START, "e4"), Ok(String::from("e4")));
+        assert_eq!(parse(&AnyNotation, START, "e2e4"), Ok(String::from("e4")));
+        assert_eq!(parse(&AnyNotation, START, "e2-e4"), Ok(String::from("e4")));
+        assert_eq!(
+            parse(&AnyNotation, "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", "O-O"),
+            Ok(String::from("O-O"))
+        );
+        assert_eq!(
+            parse(&AnyNotation, START, "e2-e5"),
+            parse(&LongAlgebraic, START, "e2-e5")
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use crate::board::constants::{BOARD_SIZE, COL_RANGE, LINE_RANGE};
 use crate::errors::{ChessPositionError, PositionError};
+use std::fmt::Display;
 
 #[derive(Copy, Clone, Debug, PartialEq, Default)]
 pub struct Position {
@@ -40,6 +41,12 @@ impl ChessPosition {
     }
 }
 
+impl Display for ChessPosition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.col, self.line)
+    }
+}
+
 impl TryFrom<ChessPosition> for Position {
     type Error = ChessPositionError;
 
@@ -1,3 +1,10 @@
-pub mod constants;
-pub mod pgn_parser;
+pub(crate) mod constants;
+mod game_result;
+pub(crate) mod pgn_parser;
 mod pgn_parser_steps;
+mod pgn_reader;
+mod pgn_tags;
+
+pub use game_result::GameResult;
+pub use pgn_reader::{PgnGame, games_from_file, games_from_reader, games_from_str};
+pub use pgn_tags::PgnTags;
@@ -1,2 +1,20 @@
 pub const CAPTURE: char = 'x';
+pub const KING_SIDE_CASTLING: &str = "O-O";
 pub const QUEEN_SIDE_CASTLING: &str = "O-O-O";
+pub const PROMOTION: char = '=';
+pub const CHECK: char = '+';
+pub const CHECKMATE: char = '#';
+pub const ANNOTATION_CHARS: [char; 2] = ['!', '?'];
+pub const TAG_START: char = '[';
+pub const TAG_END: char = ']';
+pub const COMMENT_START: char = '{';
+pub const COMMENT_END: char = '}';
+pub const LINE_COMMENT: char = ';';
+pub const ESCAPE_LINE: char = '%';
+pub const VARIATION_START: char = '(';
+pub const VARIATION_END: char = ')';
+pub const NAG_START: char = '$';
+pub const MOVE_NUMBER_DOT: char = '.';
+pub const BLACK_MOVE_NUMBER_DOTS: &str = "...";
+pub const FEN_TAG: &str = "FEN";
+pub const SETUP_TAG: &str = "SetUp";
@@ -22,6 +22,7 @@ pub fn parse_move(game_state: &GameState, pgn_move: &str) -> Result<Move, MoveEr
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::movement::{Annotation, Position};
     use crate::utils::test_helper::setup_game_state;
 
     #[test]
@@ -45,4 +46,50 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_annotation_is_recorded() -> Result<(), MoveError> {
+        let game_state = setup_game_state(None);
+
+        assert_eq!(parse_move(&game_state, "e4")?.annotation, None);
+        assert_eq!(
+            parse_move(&game_state, "e4!?")?.annotation,
+            Some(Annotation::Interesting)
+        );
+        assert_eq!(
+            parse_move(&game_state, "Nf3??")?.annotation,
+            Some(Annotation::Blunder)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_disambiguation_by_square() -> Result<(), MoveError> {
+        let game_state = GameState::from_fen("2k5/8/8/8/4Q2Q/8/8/K6Q w - - 0 1").unwrap();
+
+        let result = parse_move(&game_state, "Qh4e1")?;
+
+        assert_eq!(result.source(), Position::new(4, 7));
+        assert_eq!(result.destination(), Position::new(7, 4));
+
+        let game_state = GameState::from_fen("2k5/8/8/8/4Q2Q/8/8/K3n2Q w - - 0 1").unwrap();
+
+        let result = parse_move(&game_state, "Qh4xe1")?;
+
+        assert_eq!(result.source(), Position::new(4, 7));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_disambiguation_with_suffix() -> Result<(), MoveError> {
+        let game_state = setup_game_state(Some("tests/validate_disambiguation_same_line.txt"));
+
+        let result = parse_move(&game_state, "Ncd5!")?;
+
+        assert_eq!(result.source().col, 2);
+
+        Ok(())
+    }
 }
@@ -3,10 +3,12 @@ mod fifth;
 mod first;
 mod fourth;
 mod second;
+mod suffix;
 mod third;
 
 use fifth::Fifth;
 pub(super) use first::First;
 use fourth::Fourth;
 use second::Second;
+use suffix::Suffix;
 use third::Third;
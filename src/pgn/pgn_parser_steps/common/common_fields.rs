@@ -8,6 +8,18 @@ pub struct ParserState {
     pub castling: bool,
     pub dest_col: Option<char>,
     pub disambiguation: Option<char>,
+    /// Rank of a full-square disambiguation (e.g., the `4` of `Qh4e1`)
+    pub disambiguation_line: Option<char>,
+}
+
+impl ParserState {
+    /// Characters the source square of the move must match, either on its column or its line
+    pub fn disambiguation_chars(&self) -> Vec<char> {
+        self.disambiguation
+            .into_iter()
+            .chain(self.disambiguation_line)
+            .collect()
+    }
 }
 
 impl Default for ParserState {
@@ -18,6 +30,7 @@ impl Default for ParserState {
             castling: false,
             dest_col: None,
             disambiguation: None,
+            disambiguation_line: None,
         }
     }
 }
@@ -1,8 +1,9 @@
+use super::Suffix;
 use super::common::{CommonIters, ParserState, PgnParserStep, StepResult};
 use crate::GameState;
 use crate::errors::constants::INTERNAL_ERROR_03;
 use crate::errors::{ChessPositionError, MoveError, PgnError};
-use crate::movement::{ChessPosition, Move};
+use crate::movement::ChessPosition;
 
 pub struct Fifth<'a> {
     state: ParserState,
@@ -18,12 +19,14 @@ where
     }
 
     fn handle_castling(
-        &mut self,
+        mut self,
         current_pgn_char: char,
         game_state: &GameState,
     ) -> Result<StepResult<'b>, MoveError> {
         if current_pgn_char == self.iters.castling_chars.next().expect(INTERNAL_ERROR_03) {
-            game_state.find_castling_move(false).map(|m| m.into())
+            let castling_move = game_state.find_castling_move(false)?;
+
+            Ok(StepResult::Step(Suffix::new(castling_move, self.iters)))
         }
         else {
             Err(PgnError::InvalidCharacter(current_pgn_char).into())
@@ -37,7 +40,7 @@ impl PgnParserStep for Fifth<'_> {
         Self: 'a,
     {
         let capture = self.state.capture;
-        let disambiguation = self.state.disambiguation;
+        let disambiguation = self.state.disambiguation_chars();
         let piece_type = self.state.piece_type;
 
         let current_pgn_char = self
@@ -61,8 +64,11 @@ impl PgnParserStep for Fifth<'_> {
         let dest_line = current_pgn_char;
         let destination = ChessPosition::new(dest_line, col).try_into()?;
         let origin =
-            game_state.find_piece_position(piece_type, destination, disambiguation, capture)?;
+            game_state.find_piece_position(piece_type, destination, &disambiguation, capture)?;
 
-        Ok(StepResult::Move(Move::new(origin, destination)))
+        Ok(StepResult::Step(Suffix::new(
+            game_state.build_move(origin, destination),
+            self.iters,
+        )))
     }
 }
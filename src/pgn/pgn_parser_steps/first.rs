@@ -3,7 +3,7 @@ use super::common::{CommonIters, ParserState, PgnParserStep, StepResult};
 use crate::GameState;
 use crate::errors::constants::INTERNAL_ERROR_03;
 use crate::errors::{MoveError, PgnError};
-use crate::pgn::constants::QUEEN_SIDE_CASTLING;
+use crate::pgn::constants::{ANNOTATION_CHARS, CHECK, CHECKMATE, PROMOTION, QUEEN_SIDE_CASTLING};
 use crate::pieces::PieceType;
 
 pub struct First<'a> {
@@ -16,8 +16,14 @@ impl<'a> First<'a> {
     pub fn new(pgn_move: &'a str) -> Box<Self> {
         let iters = CommonIters::new(pgn_move.chars(), QUEEN_SIDE_CASTLING.chars());
 
+        // The promotion, check and annotation suffixes do not take part in the disambiguation
+        // heuristics
+        let pgn_len = pgn_move
+            .find(|c| [PROMOTION, CHECK, CHECKMATE].contains(&c) || ANNOTATION_CHARS.contains(&c))
+            .unwrap_or(pgn_move.len());
+
         Box::new(Self {
-            pgn_len: pgn_move.len(),
+            pgn_len,
             iters,
             state: Default::default(),
         })
@@ -1,9 +1,9 @@
-use super::Fifth;
 use super::common::{CommonIters, ParserState, PgnParserStep, StepResult};
+use super::{Fifth, Suffix};
 use crate::GameState;
 use crate::errors::constants::INTERNAL_ERROR_03;
 use crate::errors::{ChessPositionError, MoveError, PgnError};
-use crate::movement::{ChessPosition, Move};
+use crate::movement::ChessPosition;
 
 const STEP: &str = "fourth";
 
@@ -20,21 +20,19 @@ where
         Box::new(Self { state, iters })
     }
 
-    fn handle_castling(
-        mut self,
-        current_pgn_char: Option<char>,
-        game_state: &GameState,
-    ) -> Result<StepResult<'b>, MoveError> {
-        match current_pgn_char {
-            Some(pgn_char) => {
-                if pgn_char == self.iters.castling_chars.next().expect(INTERNAL_ERROR_03) {
-                    Ok(StepResult::Step(Fifth::new(self.state, self.iters)))
-                }
-                else {
-                    Err(PgnError::InvalidCharacter(pgn_char).into())
-                }
-            }
-            None => game_state.find_castling_move(true).map(|m| m.into()),
+    fn handle_castling(mut self, game_state: &GameState) -> Result<StepResult<'b>, MoveError> {
+        // "O-O" is either complete (short castling) or followed by "-O" (long castling)
+        let castling_char = self.iters.castling_chars.next().expect(INTERNAL_ERROR_03);
+
+        if self.iters.pgn_chars.as_str().starts_with(castling_char) {
+            self.iters.pgn_chars.next();
+
+            Ok(StepResult::Step(Fifth::new(self.state, self.iters)))
+        }
+        else {
+            let castling_move = game_state.find_castling_move(true)?;
+
+            Ok(StepResult::Step(Suffix::new(castling_move, self.iters)))
         }
     }
 
@@ -42,7 +40,7 @@ where
         self,
         game_state: &GameState,
         capture: bool,
-        disambiguation: Option<char>,
+        disambiguation: &[char],
         current_pgn_char: char,
     ) -> Result<StepResult<'b>, MoveError> {
         let piece_type = self.state.piece_type;
@@ -57,7 +55,10 @@ where
         let origin =
             game_state.find_piece_position(piece_type, destination, disambiguation, capture)?;
 
-        Ok(StepResult::Move(Move::new(origin, destination)))
+        Ok(StepResult::Step(Suffix::new(
+            game_state.build_move(origin, destination),
+            self.iters,
+        )))
     }
 
     fn handle_destination_column(
@@ -76,19 +77,21 @@ impl PgnParserStep for Fourth<'_> {
         Self: 'a,
     {
         let capture = self.state.capture;
-        let disambiguation = self.state.disambiguation;
+        let disambiguation = self.state.disambiguation_chars();
         let castling = self.state.castling;
 
-        let current_pgn_char = self.iters.pgn_chars.next();
-
         if castling {
-            return self.handle_castling(current_pgn_char, game_state);
+            return self.handle_castling(game_state);
         }
 
-        let current_pgn_char = current_pgn_char.ok_or(PgnError::MissingCharacter(STEP))?;
+        let current_pgn_char = self
+            .iters
+            .pgn_chars
+            .next()
+            .ok_or(PgnError::MissingCharacter(STEP))?;
 
-        if current_pgn_char.is_ascii_digit() && (capture || disambiguation.is_some()) {
-            self.handle_digit(game_state, capture, disambiguation, current_pgn_char)
+        if current_pgn_char.is_ascii_digit() && (capture || !disambiguation.is_empty()) {
+            self.handle_digit(game_state, capture, &disambiguation, current_pgn_char)
         }
         else if current_pgn_char.is_lowercase() {
             self.handle_destination_column(current_pgn_char)
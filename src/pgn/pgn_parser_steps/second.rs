@@ -1,10 +1,10 @@
-use super::Third;
 use super::common::{CommonIters, ParserState, PgnParserStep, StepResult};
+use super::{Suffix, Third};
 use crate::GameState;
 use crate::board::constants::{COL_RANGE, LINE_RANGE};
 use crate::errors::constants::INTERNAL_ERROR_03;
 use crate::errors::{MoveError, PgnError};
-use crate::movement::{ChessPosition, Move};
+use crate::movement::ChessPosition;
 use crate::pgn::constants::CAPTURE;
 use crate::pieces::PieceType;
 
@@ -48,10 +48,12 @@ where
                 let dest_line = current_pgn_char;
                 let destination = ChessPosition::new(dest_line, col).try_into()?;
 
-                let origin =
-                    game_state.find_piece_position(piece_type, destination, None, false)?;
+                let origin = game_state.find_piece_position(piece_type, destination, &[], false)?;
 
-                Ok(StepResult::Move(Move::new(origin, destination)))
+                Ok(StepResult::Step(Suffix::new(
+                    game_state.build_move(origin, destination),
+                    self.iters,
+                )))
             }
             None => {
                 self.state.disambiguation = Some(current_pgn_char);
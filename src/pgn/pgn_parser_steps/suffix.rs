@@ -0,0 +1,66 @@
+use super::common::{CommonIters, PgnParserStep, StepResult};
+use crate::GameState;
+use crate::errors::{MoveError, PgnError};
+use crate::movement::Move;
+use crate::pgn::constants::{CHECK, CHECKMATE, PROMOTION};
+use crate::pieces::PieceType;
+
+const STEP: &str = "promotion";
+
+/// Parse what follows the destination square of an already resolved move: the promotion piece
+/// (e.g., `=Q`), the check or checkmate symbol and the annotation glyph (e.g., `!?`)
+pub struct Suffix<'a> {
+    move_: Move,
+    iters: CommonIters<'a>,
+}
+
+impl<'a> Suffix<'a> {
+    pub fn new(move_: Move, iters: CommonIters<'a>) -> Box<Self> {
+        Box::new(Self { move_, iters })
+    }
+
+    fn next_if_eq(&mut self, expected: char) -> bool {
+        let matches = self.iters.pgn_chars.as_str().starts_with(expected);
+        if matches {
+            self.iters.pgn_chars.next();
+        }
+
+        matches
+    }
+}
+
+impl PgnParserStep for Suffix<'_> {
+    fn parse<'a>(mut self: Box<Self>, game_state: &GameState) -> Result<StepResult<'a>, MoveError>
+    where
+        Self: 'a,
+    {
+        let mut move_ = self.move_;
+
+        if self.next_if_eq(PROMOTION) {
+            let current_pgn_char = self
+                .iters
+                .pgn_chars
+                .next()
+                .ok_or(PgnError::MissingCharacter(STEP))?;
+            let piece_type: PieceType = current_pgn_char.try_into()?;
+
+            move_.promotion = Some(piece_type);
+        }
+
+        game_state.validate_promotion(move_)?;
+
+        let check = self.next_if_eq(CHECK);
+        let checkmate = !check && self.next_if_eq(CHECKMATE);
+
+        let annotation = self.iters.pgn_chars.as_str();
+        if !annotation.is_empty() {
+            move_.annotation = Some(annotation.try_into()?);
+        }
+
+        if check || checkmate {
+            game_state.validate_check(move_, checkmate)?;
+        }
+
+        Ok(StepResult::Move(move_))
+    }
+}
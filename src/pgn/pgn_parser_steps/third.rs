@@ -1,9 +1,10 @@
-use super::Fourth;
 use super::common::{CommonIters, ParserState, PgnParserStep, StepResult};
+use super::{Fourth, Suffix};
 use crate::GameState;
+use crate::board::constants::COL_RANGE;
 use crate::errors::constants::INTERNAL_ERROR_03;
 use crate::errors::{ChessPositionError, MoveError, PgnError};
-use crate::movement::{ChessPosition, Move};
+use crate::movement::ChessPosition;
 use crate::pgn::constants::CAPTURE;
 use crate::pieces::PieceType;
 
@@ -53,11 +54,25 @@ where
         let origin = game_state.find_piece_position(
             piece_type,
             destination,
-            self.state.disambiguation,
+            &self.state.disambiguation_chars(),
             self.state.capture,
         )?;
 
-        Ok(StepResult::Move(Move::new(origin, destination)))
+        Ok(StepResult::Step(Suffix::new(
+            game_state.build_move(origin, destination),
+            self.iters,
+        )))
+    }
+
+    /// Complete a column disambiguation into a full square (e.g., the `4` of `Qh4e1`), leaving
+    /// the capture character and the destination to be parsed next
+    fn handle_disambiguation_line(
+        mut self,
+        current_pgn_char: char,
+    ) -> Result<StepResult<'b>, MoveError> {
+        self.state.disambiguation_line = Some(current_pgn_char);
+
+        Ok(StepResult::Step(Third::new(self.state, self.iters)))
     }
 
     fn handle_destination_column(
@@ -90,6 +105,16 @@ impl PgnParserStep for Third<'_> {
         else if current_pgn_char == CAPTURE {
             self.handle_capture()
         }
+        else if current_pgn_char.is_ascii_digit()
+            && self.state.dest_col.is_none()
+            && self.state.disambiguation_line.is_none()
+            && self
+                .state
+                .disambiguation
+                .is_some_and(|disambiguation| COL_RANGE.contains(&disambiguation))
+        {
+            self.handle_disambiguation_line(current_pgn_char)
+        }
         else if current_pgn_char.is_ascii_digit() && !matches!(piece_type, PieceType::Pawn(_)) {
             self.handle_digit(game_state, piece_type, current_pgn_char)
         }
@@ -0,0 +1,421 @@
+use super::GameResult;
+use super::constants::{
+    COMMENT_END, COMMENT_START, ESCAPE_LINE, FEN_TAG, LINE_COMMENT, MOVE_NUMBER_DOT, NAG_START,
+    TAG_END, TAG_START, VARIATION_END, VARIATION_START,
+};
+use crate::GameState;
+use crate::errors::PgnReadError;
+use crate::io::file_manager::open_file;
+use std::io::BufRead;
+use std::iter::Peekable;
+use std::mem::replace;
+use std::path::Path;
+use std::str::Chars;
+
+const SYMBOL_DELIMITERS: [char; 8] = [
+    TAG_START,
+    TAG_END,
+    COMMENT_START,
+    COMMENT_END,
+    LINE_COMMENT,
+    VARIATION_START,
+    VARIATION_END,
+    NAG_START,
+];
+
+#[derive(Clone, Debug, Default, PartialEq)]
+/// A game read from PGN text: its tag pairs, its moves in SAN and its result
+pub struct PgnGame {
+    number: usize,
+    tags: Vec<(String, String)>,
+    moves: Vec<String>,
+    result: GameResult,
+}
+
+impl PgnGame {
+    fn new(number: usize) -> Self {
+        Self {
+            number,
+            ..Default::default()
+        }
+    }
+
+    /// Position of the game in its PGN text, starting at 1
+    pub fn number(&self) -> usize {
+        self.number
+    }
+
+    /// Tag pairs, in the order they were written
+    pub fn tags(&self) -> &[(String, String)] {
+        &self.tags
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag_name, _)| tag_name == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Moves of the main line, in SAN, without move numbers, comments or variations
+    pub fn moves(&self) -> &[String] {
+        &self.moves
+    }
+
+    pub fn result(&self) -> GameResult {
+        self.result
+    }
+
+    /// Play every move of the game, starting from the position of its FEN tag if any, and return
+    /// the final game state
+    pub fn replay(&self) -> Result<GameState, PgnReadError> {
+        let mut game_state = match self.tag(FEN_TAG) {
+            Some(fen) => GameState::from_fen(fen).map_err(|error| PgnReadError::InvalidFen {
+                game: self.number,
+                error,
+            })?,
+            None => GameState::starting_position(),
+        };
+
+        for (index, san) in self.moves.iter().enumerate() {
+            game_state
+                .handle_move(san)
+                .map_err(|error| PgnReadError::IllegalMove {
+                    game: self.number,
+                    ply: index + 1,
+                    san: san.clone(),
+                    error,
+                })?;
+        }
+
+        Ok(game_state)
+    }
+}
+
+/// Read every game of a PGN file
+pub fn games_from_file(path: impl AsRef<Path>) -> Result<Vec<PgnGame>, PgnReadError> {
+    games_from_reader(open_file(path.as_ref())?)
+}
+
+/// Read every game from a reader yielding PGN text
+pub fn games_from_reader(mut reader: impl BufRead) -> Result<Vec<PgnGame>, PgnReadError> {
+    let mut pgn = String::new();
+    reader.read_to_string(&mut pgn)?;
+
+    games_from_str(&pgn)
+}
+
+/// Split PGN text into games. Comments, numeric annotation glyphs and variations are skipped;
+/// moves are only checked when a game is replayed.
+pub fn games_from_str(pgn: &str) -> Result<Vec<PgnGame>, PgnReadError> {
+    let mut lexer = Lexer::new(pgn);
+    let mut games = Vec::new();
+    let mut game = PgnGame::new(1);
+    let mut variation_depth = 0;
+    let mut variation_line = 0;
+
+    while let Some(token) = lexer.next_token()? {
+        match token {
+            Token::VariationStart => {
+                if variation_depth == 0 {
+                    variation_line = lexer.line;
+                }
+                variation_depth += 1;
+            }
+            Token::VariationEnd if variation_depth == 0 => {
+                return Err(PgnReadError::UnbalancedVariation(lexer.line));
+            }
+            Token::VariationEnd => variation_depth -= 1,
+            _ if variation_depth > 0 => {}
+            Token::Tag(name, value) => {
+                if !game.moves.is_empty() {
+                    return Err(PgnReadError::MissingResult(game.number));
+                }
+                game.tags.push((name, value));
+            }
+            Token::Move(san) => game.moves.push(san),
+            Token::Result(result) => {
+                game.result = result;
+                let next_game = PgnGame::new(game.number + 1);
+                games.push(replace(&mut game, next_game));
+            }
+        }
+    }
+
+    if variation_depth > 0 {
+        return Err(PgnReadError::UnbalancedVariation(variation_line));
+    }
+    if !game.tags.is_empty() || !game.moves.is_empty() {
+        return Err(PgnReadError::MissingResult(game.number));
+    }
+
+    Ok(games)
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Tag(String, String),
+    Move(String),
+    Result(GameResult),
+    VariationStart,
+    VariationEnd,
+}
+
+struct Lexer<'a> {
+    chars: Peekable<Chars<'a>>,
+    /// Current line, starting at 1
+    line: usize,
+    at_line_start: bool,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(pgn: &'a str) -> Self {
+        Self {
+            chars: pgn.chars().peekable(),
+            line: 1,
+            at_line_start: true,
+        }
+    }
+
+    fn next_char(&mut self) -> Option<char> {
+        let next_char = self.chars.next()?;
+        self.at_line_start = next_char == '\n';
+        if self.at_line_start {
+            self.line += 1;
+        }
+
+        Some(next_char)
+    }
+
+    fn next_token(&mut self) -> Result<Option<Token>, PgnReadError> {
+        loop {
+            let at_line_start = self.at_line_start;
+            let Some(next_char) = self.next_char()
+            else {
+                return Ok(None);
+            };
+
+            match next_char {
+                ESCAPE_LINE if at_line_start => self.skip_line(),
+                LINE_COMMENT => self.skip_line(),
+                COMMENT_START => self.skip_comment()?,
+                TAG_START => return self.read_tag().map(Some),
+                VARIATION_START => return Ok(Some(Token::VariationStart)),
+                VARIATION_END => return Ok(Some(Token::VariationEnd)),
+                NAG_START => {
+                    self.read_symbol(NAG_START);
+                }
+                _ if next_char.is_whitespace() => {}
+                _ => {
+                    let symbol = self.read_symbol(next_char);
+                    if let Some(result) = GameResult::from_token(&symbol) {
+                        return Ok(Some(Token::Result(result)));
+                    }
+
+                    // Move numbers may be glued to the move that follows them (e.g., `1.e4`)
+                    let san = symbol
+                        .trim_start_matches(|symbol_char: char| symbol_char.is_ascii_digit())
+                        .trim_start_matches(MOVE_NUMBER_DOT);
+                    if !san.is_empty() {
+                        return Ok(Some(Token::Move(san.to_owned())));
+                    }
+                }
+            }
+        }
+    }
+
+    fn read_symbol(&mut self, first_char: char) -> String {
+        let mut symbol = first_char.to_string();
+        while let Some(&next_char) = self.chars.peek()
+            && !next_char.is_whitespace()
+            && !SYMBOL_DELIMITERS.contains(&next_char)
+        {
+            symbol.push(next_char);
+            self.next_char();
+        }
+
+        symbol
+    }
+
+    fn skip_line(&mut self) {
+        while let Some(next_char) = self.next_char()
+            && next_char != '\n'
+        {}
+    }
+
+    fn skip_comment(&mut self) -> Result<(), PgnReadError> {
+        let start_line = self.line;
+        while let Some(next_char) = self.next_char() {
+            if next_char == COMMENT_END {
+                return Ok(());
+            }
+        }
+
+        Err(PgnReadError::UnterminatedComment(start_line))
+    }
+
+    /// Read a tag pair such as `[Event "Club championship"]`, whose opening bracket has already
+    /// been consumed
+    fn read_tag(&mut self) -> Result<Token, PgnReadError> {
+        let line = self.line;
+        self.skip_spaces();
+
+        let mut name = String::new();
+        while let Some(&next_char) = self.chars.peek()
+            && (next_char.is_ascii_alphanumeric() || next_char == '_')
+        {
+            name.push(next_char);
+            self.next_char();
+        }
+
+        self.skip_spaces();
+        if name.is_empty() || self.next_char() != Some('"') {
+            return Err(PgnReadError::InvalidTag(line));
+        }
+
+        let mut value = String::new();
+        loop {
+            match self.next_char() {
+                Some('"') => break,
+                Some('\\') => match self.next_char() {
+                    Some(escaped @ ('"' | '\\')) => value.push(escaped),
+                    _ => return Err(PgnReadError::InvalidTag(line)),
+                },
+                Some('\n') | None => return Err(PgnReadError::InvalidTag(line)),
+                Some(next_char) => value.push(next_char),
+            }
+        }
+
+        self.skip_spaces();
+        if self.next_char() != Some(TAG_END) {
+            return Err(PgnReadError::InvalidTag(line));
+        }
+
+        Ok(Token::Tag(name, value))
+    }
+
+    /// Skip the whitespace within a line, tabs included, leaving line breaks to be counted
+    fn skip_spaces(&mut self) {
+        while self
+            .chars
+            .next_if(|next_char| next_char.is_whitespace() && *next_char != '\n')
+            .is_some()
+        {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::MoveError;
+
+    #[test]
+    fn test_tags_moves_and_result() -> Result<(), PgnReadError> {
+        let pgn = "[Event \"Club \\\"open\\\"\"]\n[White \"Alice\"]\n\n1. e4 e5 2. Nf3 1/2-1/2\n";
+
+        let games = games_from_str(pgn)?;
+
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].number(), 1);
+        assert_eq!(games[0].tag("Event"), Some("Club \"open\""));
+        assert_eq!(games[0].tag("White"), Some("Alice"));
+        assert_eq!(games[0].tag("Black"), None);
+        assert_eq!(games[0].moves(), ["e4", "e5", "Nf3"]);
+        assert_eq!(games[0].result(), GameResult::Draw);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tags_separated_by_tabs() -> Result<(), PgnReadError> {
+        let pgn = "[Event\t\"Club\"]\r\n[ White \t\"Alice\"\t]\n\n1. e4 *\n";
+
+        let games = games_from_str(pgn)?;
+
+        assert_eq!(games[0].tag("Event"), Some("Club"));
+        assert_eq!(games[0].tag("White"), Some("Alice"));
+        assert_eq!(games[0].moves(), ["e4"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_comments_variations_and_glyphs_are_skipped() -> Result<(), PgnReadError> {
+        let pgn = "% exported by a database\n\
+                   1.e4 {best by test; (really)} e5 $1 ; a line comment\n\
+                   2. Nf3 (2. f4 exf4 (2... d5) 3. Nf3) 2... Nc6! *";
+
+        let games = games_from_str(pgn)?;
+
+        assert_eq!(games[0].moves(), ["e4", "e5", "Nf3", "Nc6!"]);
+        assert_eq!(games[0].result(), GameResult::Unknown);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_several_games() -> Result<(), PgnReadError> {
+        let pgn = "[Result \"1-0\"]\n1. e4 1-0\n\n[Result \"0-1\"]\n1. f3 e5 2. g4 Qh4# 0-1\n";
+
+        let games = games_from_str(pgn)?;
+
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[1].number(), 2);
+        assert_eq!(games[1].result(), GameResult::BlackWins);
+        assert!(games[1].replay()?.verify_checkmate());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        let cases = [
+            ("[Event \"Open]\n1. e4 *", PgnReadError::InvalidTag(1)),
+            ("[Event Open]\n1. e4 *", PgnReadError::InvalidTag(1)),
+            (
+                "1. e4\n{ never closed\n*",
+                PgnReadError::UnterminatedComment(2),
+            ),
+            ("1. e4 (1. d4\n*", PgnReadError::UnbalancedVariation(1)),
+            ("1. e4 e5\n2. Nf3) *", PgnReadError::UnbalancedVariation(2)),
+            ("1. e4 e5", PgnReadError::MissingResult(1)),
+            (
+                "1. e4 *\n1. d4\n[Event \"?\"]",
+                PgnReadError::MissingResult(2),
+            ),
+        ];
+
+        for (pgn, expected_error) in cases {
+            assert_eq!(games_from_str(pgn), Err(expected_error), "{pgn}");
+        }
+    }
+
+    #[test]
+    fn test_replay_reports_game_and_ply() -> Result<(), PgnReadError> {
+        let games = games_from_str("1. e4 e5 *\n1. e4 e5 2. Ke3 *")?;
+
+        assert!(games[0].replay().is_ok());
+        assert_eq!(
+            games[1].replay(),
+            Err(PgnReadError::IllegalMove {
+                game: 2,
+                ply: 3,
+                san: "Ke3".to_owned(),
+                error: MoveError::NoPieceAvailable,
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_replay_from_fen_tag() -> Result<(), PgnReadError> {
+        let pgn = "[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/8/R3K3 w Q - 0 1\"]\n\n1. O-O-O Kf7 *";
+
+        let game_state = games_from_str(pgn)?[0].replay()?;
+
+        assert_eq!(game_state.to_fen(), "8/5k2/8/8/8/8/8/2KR4 w - - 2 2");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+#[derive(Clone, Debug, PartialEq)]
+/// Tags of the Seven Tag Roster written when exporting a game. The result tag is left out, since
+/// it is derived from the game itself.
+pub struct PgnTags {
+    pub event: String,
+    pub site: String,
+    /// Date in the `YYYY.MM.DD` format, with `??` for unknown parts
+    pub date: String,
+    pub round: String,
+    pub white: String,
+    pub black: String,
+}
+
+impl Default for PgnTags {
+    fn default() -> Self {
+        Self {
+            event: "?".to_owned(),
+            site: "?".to_owned(),
+            date: "????.??.??".to_owned(),
+            round: "?".to_owned(),
+            white: "?".to_owned(),
+            black: "?".to_owned(),
+        }
+    }
+}
+
+impl PgnTags {
+    /// Tag names and values, in the order of the Seven Tag Roster
+    pub fn pairs(&self) -> [(&'static str, &str); 6] {
+        [
+            ("Event", &self.event),
+            ("Site", &self.site),
+            ("Date", &self.date),
+            ("Round", &self.round),
+            ("White", &self.white),
+            ("Black", &self.black),
+        ]
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Color {
     White,
@@ -5,6 +7,13 @@ pub enum Color {
 }
 
 impl Color {
+    pub fn opposite(self) -> Color {
+        match self {
+            Color::Black => Color::White,
+            Color::White => Color::Black,
+        }
+    }
+
     pub fn flip(&mut self) {
         *self = match self {
             Color::Black => Color::White,
@@ -24,3 +33,12 @@ impl TryFrom<char> for Color {
         }
     }
 }
+
+impl Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Color::White => write!(f, "White"),
+            Color::Black => write!(f, "Black"),
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use crate::errors::PgnError;
+use crate::errors::constants::INTERNAL_ERROR_04;
 use crate::pieces::types::King as KingStruct;
 use crate::pieces::types::Pawn as PawnStruct;
 use crate::pieces::types::Rook as RookStruct;
@@ -15,6 +16,31 @@ pub enum PieceType {
     None,
 }
 
+impl PieceType {
+    /// Piece types a pawn can be promoted to
+    pub fn promotion_types() -> [PieceType; 4] {
+        [
+            PieceType::Queen,
+            PieceType::Rook(Default::default()),
+            PieceType::Bishop,
+            PieceType::Knight,
+        ]
+    }
+
+    /// Uppercase letter identifying the piece type in PGN and FEN (e.g., `N` for a Knight)
+    pub fn letter(&self) -> char {
+        match self {
+            Self::Bishop => 'B',
+            Self::King(_) => 'K',
+            Self::Knight => 'N',
+            Self::Pawn(_) => 'P',
+            Self::Queen => 'Q',
+            Self::Rook(_) => 'R',
+            Self::None => panic!("{}", INTERNAL_ERROR_04),
+        }
+    }
+}
+
 impl TryFrom<char> for PieceType {
     type Error = PgnError;
 
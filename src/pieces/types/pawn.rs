@@ -1,6 +1,7 @@
 use super::super::Color;
 use super::super::Piece;
 use crate::Board;
+use crate::board::constants::BOARD_SIZE;
 use crate::movement::{Position, PositionI8};
 
 pub const SYMBOLS: [char; 2] = ['\u{2659}', '\u{265F}'];
@@ -59,6 +60,19 @@ impl Pawn {
         }
     }
 
+    /// Whether a pawn of the given color standing on `position` has reached the last rank
+    pub fn is_last_rank(piece_color: Color, position: Position) -> bool {
+        match piece_color {
+            Color::White => position.line == 0,
+            Color::Black => position.line == BOARD_SIZE - 1,
+        }
+    }
+
+    /// Pawns can never stand on the first or last rank
+    pub fn is_back_rank(position: Position) -> bool {
+        Self::is_last_rank(Color::White, position) || Self::is_last_rank(Color::Black, position)
+    }
+
     pub fn get_possible_moves(
         &self,
         piece_color: Color,
@@ -261,6 +275,14 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_is_last_rank() {
+        assert!(Pawn::is_last_rank(Color::White, Position::new(0, 4)));
+        assert!(Pawn::is_last_rank(Color::Black, Position::new(7, 4)));
+        assert!(!Pawn::is_last_rank(Color::White, Position::new(7, 4)));
+        assert!(!Pawn::is_last_rank(Color::Black, Position::new(1, 4)));
+    }
+
     #[test]
     fn test_get_possible_moves_unblocked_white_pawn() {
         let board = setup_board(Some("tests/pawn/only_white_pawn.txt"));
@@ -0,0 +1,136 @@
+//! Text protocols through which chess GUIs and tournament managers drive the engine, one command
+//! per line on the standard input and the answers on the standard output
+
+pub mod uci;
+pub mod xboard;
+
+use crate::GameState;
+use crate::engine::{Engine, SearchLimits, SearchResult};
+use crate::errors::constants::INTERNAL_ERROR_10;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// Moves left to play assumed when the time control does not say
+const DEFAULT_MOVES_TO_GO: u32 = 30;
+/// Time kept aside for the communication with the GUI, so that the clock does not run out
+const MOVE_OVERHEAD: Duration = Duration::from_millis(50);
+
+/// Output shared between the thread reading the commands and the one searching
+type SharedOutput<W> = Arc<Mutex<W>>;
+
+/// Lock a value shared with the search thread, which cannot leave it inconsistent by panicking
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Write a line to the GUI, which waits for it: the output is flushed right away
+fn send<W: Write>(output: &Mutex<W>, line: &str) {
+    let mut output = lock(output);
+    // The GUI closing the pipe is noticed when reading the next command
+    let _ = writeln!(output, "{line}").and_then(|_| output.flush());
+}
+
+/// Time to think on the next move, given the time left on the clock and the increment
+fn time_for_move(remaining: Duration, increment: Duration, moves_to_go: Option<u32>) -> Duration {
+    let moves_to_go = moves_to_go.unwrap_or(DEFAULT_MOVES_TO_GO).max(1);
+    let budget = remaining / moves_to_go + increment * 3 / 4;
+
+    budget
+        .min(remaining.saturating_sub(MOVE_OVERHEAD))
+        .max(Duration::from_millis(1))
+}
+
+/// Engine owned by a protocol, lent to a thread while it searches so that commands such as
+/// `stop` can still be read
+#[derive(Debug)]
+struct SearchThread {
+    /// `None` while a search is running
+    engine: Option<Engine>,
+    search: Option<JoinHandle<Engine>>,
+    stop_signal: Arc<AtomicBool>,
+    /// Whether the running search only reports its result once stopped
+    wait_for_stop: bool,
+}
+
+impl SearchThread {
+    fn new(engine: Engine) -> Self {
+        Self {
+            stop_signal: engine.stop_signal(),
+            engine: Some(engine),
+            search: None,
+            wait_for_stop: false,
+        }
+    }
+
+    /// Search on a new thread, calling `progress` after each iteration and `done` with the
+    /// final result. When `wait_for_stop` is set, `done` is delayed until the search is stopped,
+    /// even if it ended on its own.
+    fn start(
+        &mut self,
+        game_state: GameState,
+        limits: SearchLimits,
+        wait_for_stop: bool,
+        mut progress: impl FnMut(&SearchResult) + Send + 'static,
+        done: impl FnOnce(SearchResult) + Send + 'static,
+    ) {
+        self.stop();
+        let mut engine = self.engine.take().expect(INTERNAL_ERROR_10);
+        let stop_signal = Arc::clone(&self.stop_signal);
+        stop_signal.store(false, Ordering::Relaxed);
+        self.wait_for_stop = wait_for_stop;
+
+        self.search = Some(thread::spawn(move || {
+            let result = engine.search_with_progress(&game_state, limits, &mut progress);
+
+            if wait_for_stop {
+                while !stop_signal.load(Ordering::Relaxed) {
+                    thread::park();
+                }
+            }
+            done(result);
+
+            engine
+        }));
+    }
+
+    /// Stop the search if one is running, and wait for it to report its result
+    fn stop(&mut self) {
+        if let Some(search) = &self.search {
+            self.stop_signal.store(true, Ordering::Relaxed);
+            search.thread().unpark();
+        }
+        self.wait();
+    }
+
+    /// Let the search running, if any, end on its own, unless it would wait to be stopped
+    fn finish(&mut self) {
+        match self.wait_for_stop {
+            true => self.stop(),
+            false => self.wait(),
+        }
+    }
+
+    fn wait(&mut self) {
+        if let Some(search) = self.search.take() {
+            let engine = search
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+            self.engine = Some(engine);
+        }
+    }
+
+    /// The engine, once any search running has been stopped
+    fn engine(&mut self) -> &mut Engine {
+        self.stop();
+        self.engine.as_mut().expect(INTERNAL_ERROR_10)
+    }
+
+    fn replace_engine(&mut self, engine: Engine) {
+        self.stop();
+        self.stop_signal = engine.stop_signal();
+        self.engine = Some(engine);
+    }
+}
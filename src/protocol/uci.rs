@@ -0,0 +1,385 @@
+//! Universal Chess Interface, spoken by most chess GUIs and tournament managers. Moves are
+//! exchanged in coordinate notation (e.g., `e2e4` or `e7e8q`).
+
+use super::{SearchThread, SharedOutput, send, time_for_move};
+use crate::GameState;
+use crate::engine::{DEFAULT_SIZE_MB, Engine, SearchLimits, SearchResult};
+use crate::movement::parse_coordinate_move;
+use std::io::{BufRead, Write};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const ENGINE_NAME: &str = concat!("Rusty Chess ", env!("CARGO_PKG_VERSION"));
+const ENGINE_AUTHOR: &str = "the Rusty Chess developers";
+const HASH_OPTION: &str = "Hash";
+const MAX_HASH_SIZE_MB: usize = 4096;
+/// Sent as the best move when the side to move has no legal move
+const NULL_MOVE: &str = "0000";
+
+#[derive(Debug, Default, PartialEq)]
+/// Parameters of the `go` command
+struct GoParameters {
+    depth: Option<usize>,
+    nodes: Option<u64>,
+    move_time: Option<Duration>,
+    white_time: Option<Duration>,
+    black_time: Option<Duration>,
+    white_increment: Option<Duration>,
+    black_increment: Option<Duration>,
+    moves_to_go: Option<u32>,
+    infinite: bool,
+}
+
+impl GoParameters {
+    fn parse<'a>(mut words: impl Iterator<Item = &'a str>) -> Self {
+        let mut parameters = Self::default();
+        let millis = |value: Option<&str>| {
+            value
+                .and_then(|value| value.parse::<i64>().ok())
+                .map(|millis| Duration::from_millis(millis.max(0) as u64))
+        };
+
+        // Parameters the engine does not support, such as `ponder`, are ignored
+        while let Some(word) = words.next() {
+            match word {
+                "depth" => parameters.depth = words.next().and_then(|value| value.parse().ok()),
+                "nodes" => parameters.nodes = words.next().and_then(|value| value.parse().ok()),
+                "movetime" => parameters.move_time = millis(words.next()),
+                "wtime" => parameters.white_time = millis(words.next()),
+                "btime" => parameters.black_time = millis(words.next()),
+                "winc" => parameters.white_increment = millis(words.next()),
+                "binc" => parameters.black_increment = millis(words.next()),
+                "movestogo" => {
+                    parameters.moves_to_go = words.next().and_then(|value| value.parse().ok())
+                }
+                "infinite" => parameters.infinite = true,
+                _ => {}
+            }
+        }
+
+        parameters
+    }
+
+    /// Limits of the search for the side to move, which thinks until stopped when there are none
+    fn limits(&self, is_white_turn: bool) -> SearchLimits {
+        if self.infinite {
+            return SearchLimits::default();
+        }
+
+        let (clock, increment) = match is_white_turn {
+            true => (self.white_time, self.white_increment),
+            false => (self.black_time, self.black_increment),
+        };
+        let clock_time = clock.map(|remaining| {
+            time_for_move(remaining, increment.unwrap_or_default(), self.moves_to_go)
+        });
+
+        SearchLimits {
+            depth: self.depth,
+            nodes: self.nodes,
+            time: self.move_time.or(clock_time),
+        }
+    }
+}
+
+/// Engine driven through UCI, reading commands line by line and answering on `output`
+pub struct Uci<W> {
+    output: SharedOutput<W>,
+    game_state: GameState,
+    search: SearchThread,
+}
+
+impl<W: Write + Send + 'static> Uci<W> {
+    pub fn new(output: W) -> Self {
+        Self {
+            output: Arc::new(Mutex::new(output)),
+            game_state: GameState::starting_position(),
+            search: SearchThread::new(Engine::new()),
+        }
+    }
+
+    /// Handle commands until `quit` or the end of the input, letting the last search finish
+    pub fn run(&mut self, input: impl BufRead) {
+        for line in input.lines() {
+            let Ok(line) = line
+            else {
+                break;
+            };
+            if !self.handle_command(&line) {
+                return;
+            }
+        }
+
+        self.search.finish();
+    }
+
+    /// Handle a single command, and return whether more commands should be read
+    pub fn handle_command(&mut self, command: &str) -> bool {
+        let mut words = command.split_whitespace();
+
+        match words.next() {
+            Some("uci") => self.identify(),
+            Some("isready") => self.send("readyok"),
+            Some("setoption") => self.set_option(&words.collect::<Vec<_>>()),
+            Some("ucinewgame") => {
+                self.search.engine().new_game();
+                self.game_state = GameState::starting_position();
+            }
+            Some("position") => self.set_position(&words.collect::<Vec<_>>()),
+            Some("go") => self.go(GoParameters::parse(words)),
+            Some("stop") => self.search.stop(),
+            Some("quit") => {
+                self.search.stop();
+                return false;
+            }
+            Some(unknown) => self.send(&format!("info string Unknown command: {unknown}")),
+            None => {}
+        }
+
+        true
+    }
+
+    fn send(&self, line: &str) {
+        send(&self.output, line);
+    }
+
+    fn identify(&self) {
+        self.send(&format!("id name {ENGINE_NAME}"));
+        self.send(&format!("id author {ENGINE_AUTHOR}"));
+        self.send(&format!(
+            "option name {HASH_OPTION} type spin default {DEFAULT_SIZE_MB} min 0 max {MAX_HASH_SIZE_MB}"
+        ));
+        self.send("uciok");
+    }
+
+    /// `setoption name <name> value <value>`, where only the hash size is supported
+    fn set_option(&mut self, words: &[&str]) {
+        match words {
+            ["name", HASH_OPTION, "value", size] => match size.parse::<usize>() {
+                Ok(size_mb) if size_mb <= MAX_HASH_SIZE_MB => {
+                    self.search.replace_engine(Engine::with_hash_size(size_mb));
+                }
+                _ => self.send(&format!("info string Invalid {HASH_OPTION} size: {size}")),
+            },
+            _ => self.send(&format!(
+                "info string Unsupported option: {}",
+                words.join(" ")
+            )),
+        }
+    }
+
+    /// `position startpos|fen <fen> [moves <move>...]`, keeping the current position when the
+    /// new one is invalid
+    fn set_position(&mut self, words: &[&str]) {
+        match parse_position(words) {
+            Ok(game_state) => self.game_state = game_state,
+            Err(err) => self.send(&format!("info string {err}")),
+        }
+    }
+
+    fn go(&mut self, parameters: GoParameters) {
+        let limits = parameters.limits(self.game_state.is_white_turn());
+        let start = Instant::now();
+        let progress_output = Arc::clone(&self.output);
+        let done_output = Arc::clone(&self.output);
+
+        self.search.start(
+            self.game_state.clone(),
+            limits,
+            parameters.infinite,
+            move |result| send(&progress_output, &info(result, start.elapsed())),
+            move |result| {
+                let best_move = result
+                    .best_move
+                    .map_or(NULL_MOVE.to_owned(), |best_move| best_move.to_string());
+                send(&done_output, &format!("bestmove {best_move}"));
+            },
+        );
+    }
+}
+
+fn parse_position(words: &[&str]) -> Result<GameState, String> {
+    let moves_index = words
+        .iter()
+        .position(|word| *word == "moves")
+        .unwrap_or(words.len());
+
+    let mut game_state = match &words[..moves_index] {
+        ["startpos"] => GameState::starting_position(),
+        ["fen", fen @ ..] if !fen.is_empty() => {
+            GameState::from_fen(&fen.join(" ")).map_err(|err| err.to_string())?
+        }
+        _ => {
+            return Err(String::from(
+                "Expected: position startpos|fen <fen> [moves <move>...]",
+            ));
+        }
+    };
+
+    for text in words.iter().skip(moves_index + 1) {
+        parse_coordinate_move(&game_state, text)
+            .and_then(|next_move| game_state.play_move(next_move))
+            .map_err(|err| format!("{text}: {err}"))?;
+    }
+
+    Ok(game_state)
+}
+
+/// Progress of the search, after each iteration
+fn info(result: &SearchResult, elapsed: Duration) -> String {
+    let score = match result.mate_in() {
+        Some(moves) => format!("mate {moves}"),
+        None => format!("cp {}", result.score),
+    };
+    let nodes_per_second = (result.nodes as f64 / elapsed.as_secs_f64().max(0.001)) as u64;
+    let principal_variation: Vec<String> = result
+        .principal_variation
+        .iter()
+        .map(|next_move| next_move.to_string())
+        .collect();
+
+    format!(
+        "info depth {} score {score} nodes {} nps {nodes_per_second} time {} pv {}",
+        result.depth,
+        result.nodes,
+        elapsed.as_millis(),
+        principal_variation.join(" ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_helper::SharedBuffer;
+
+    fn run(commands: &str) -> (Uci<SharedBuffer>, Vec<String>) {
+        let buffer = SharedBuffer::default();
+        let mut uci = Uci::new(buffer.clone());
+
+        uci.run(commands.as_bytes());
+
+        (uci, buffer.lines())
+    }
+
+    #[test]
+    fn test_handshake() {
+        let (_, lines) = run("uci\nisready\n");
+
+        assert!(lines[0].starts_with("id name "));
+        assert!(
+            lines
+                .iter()
+                .any(|line| line.starts_with("option name Hash"))
+        );
+        assert_eq!(lines[lines.len() - 2..], ["uciok", "readyok"]);
+    }
+
+    #[test]
+    fn test_position_with_moves() {
+        let (uci, _) = run("position startpos moves e2e4 c7c5 g1f3\n");
+
+        assert_eq!(
+            uci.game_state.to_fen(),
+            "rnbqkbnr/pp1ppppp/8/2p5/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2"
+        );
+
+        let (uci, _) = run("position fen 4k3/1P6/8/8/8/8/8/4K3 w - - 0 1 moves b7b8q e8d7\n");
+
+        assert_eq!(uci.game_state.to_fen(), "1Q6/3k4/8/8/8/8/8/4K3 w - - 1 2");
+    }
+
+    #[test]
+    fn test_invalid_position_is_reported() {
+        let (uci, lines) = run("position startpos moves e2e4\nposition startpos moves e2e5\n");
+
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].starts_with("info string e2e5: "));
+        assert_eq!(uci.game_state.history().len(), 1);
+
+        let (_, lines) = run("position fen 8/8/8 w - - 0 1\nposition\n");
+        assert_eq!(lines.len(), 2);
+    }
+
+    #[test]
+    fn test_go_reports_progress_and_best_move() {
+        let (_, lines) = run("position fen 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1\ngo depth 3\n");
+
+        assert!(lines[0].starts_with("info depth 1 score "));
+        assert!(lines.iter().any(|line| line.contains("score mate 1")
+            && line.contains(" nps ")
+            && line.ends_with("pv a1a8")));
+        assert_eq!(lines.last().unwrap(), "bestmove a1a8");
+    }
+
+    #[test]
+    fn test_no_legal_move() {
+        let (_, lines) = run("position fen 7k/5Q2/6K1/8/8/8/8/8 b - - 0 1\ngo depth 2\n");
+
+        assert_eq!(lines.last().unwrap(), "bestmove 0000");
+    }
+
+    #[test]
+    fn test_infinite_search_waits_for_stop() {
+        let buffer = SharedBuffer::default();
+        let mut uci = Uci::new(buffer.clone());
+
+        uci.handle_command("position startpos moves e2e4");
+        uci.handle_command("go infinite");
+        assert!(uci.search.search.is_some());
+        uci.handle_command("isready");
+        uci.handle_command("stop");
+
+        let lines = buffer.lines();
+        assert!(lines.contains(&String::from("readyok")));
+        assert_eq!(
+            lines
+                .iter()
+                .filter(|line| line.starts_with("bestmove "))
+                .count(),
+            1
+        );
+        assert!(lines.last().unwrap().starts_with("bestmove "));
+        assert!(uci.search.search.is_none());
+    }
+
+    #[test]
+    fn test_quit_stops_reading() {
+        let (_, lines) = run("quit\nisready\n");
+
+        assert!(lines.is_empty());
+    }
+
+    #[test]
+    fn test_set_hash_option() {
+        let (_, lines) = run("setoption name Hash value 1\nsetoption name Hash value x\n");
+
+        assert_eq!(lines, ["info string Invalid Hash size: x"]);
+    }
+
+    #[test]
+    fn test_go_parameters() {
+        let parameters =
+            GoParameters::parse("wtime 60000 btime 30000 winc 1000 binc 0 movestogo 10".split(' '));
+
+        assert_eq!(parameters.white_time, Some(Duration::from_secs(60)));
+        assert_eq!(parameters.moves_to_go, Some(10));
+        assert_eq!(
+            parameters.limits(true).time,
+            Some(Duration::from_millis(6750))
+        );
+        assert_eq!(parameters.limits(false).time, Some(Duration::from_secs(3)));
+
+        let parameters = GoParameters::parse("depth 5 nodes 1000 movetime 200".split(' '));
+        assert_eq!(
+            parameters.limits(true),
+            SearchLimits {
+                depth: Some(5),
+                nodes: Some(1000),
+                time: Some(Duration::from_millis(200)),
+            }
+        );
+
+        let parameters = GoParameters::parse("infinite depth 5".split(' '));
+        assert_eq!(parameters.limits(true), SearchLimits::default());
+    }
+}
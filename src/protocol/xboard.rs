@@ -0,0 +1,508 @@
+//! Chess Engine Communication Protocol (version 2), spoken by XBoard, WinBoard and older
+//! tournament tools. The engine plays one side of a [`GameState`], shared with the thread it
+//! searches on so that its moves are played as soon as they are found.
+
+use super::{SearchThread, SharedOutput, lock, send, time_for_move};
+use crate::GameState;
+use crate::engine::{Engine, SearchLimits, SearchResult};
+use crate::errors::constants::INTERNAL_ERROR_09;
+use crate::movement::{is_coordinate_move, parse_coordinate_move};
+use crate::pieces::Color;
+use std::io::{BufRead, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const FEATURES: [&str; 10] = [
+    concat!("myname=\"Rusty Chess ", env!("CARGO_PKG_VERSION"), "\""),
+    "ping=1",
+    "setboard=1",
+    "usermove=1",
+    "colors=0",
+    "analyze=0",
+    "sigint=0",
+    "sigterm=0",
+    "reuse=1",
+    "done=1",
+];
+/// Time control until a `level` command sets another one: 40 moves in 5 minutes
+const DEFAULT_MOVES_PER_SESSION: u32 = 40;
+const DEFAULT_BASE_TIME: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, PartialEq)]
+/// Time control set by `level`, `st` and `sd`, and the engine's clock given by `time`
+struct TimeControl {
+    /// Moves to play before the clock is refilled, or 0 for the whole game
+    moves_per_session: u32,
+    base: Duration,
+    increment: Duration,
+    /// Exact time to think on each move, overriding the clock
+    move_time: Option<Duration>,
+    depth: Option<usize>,
+    clock: Duration,
+}
+
+impl Default for TimeControl {
+    fn default() -> Self {
+        Self {
+            moves_per_session: DEFAULT_MOVES_PER_SESSION,
+            base: DEFAULT_BASE_TIME,
+            increment: Duration::ZERO,
+            move_time: None,
+            depth: None,
+            clock: DEFAULT_BASE_TIME,
+        }
+    }
+}
+
+impl TimeControl {
+    /// `level <moves per session> <minutes[:seconds]> <increment seconds>`
+    fn set_level(&mut self, words: &[&str]) -> Option<()> {
+        let [moves_per_session, base, increment] = words
+        else {
+            return None;
+        };
+
+        let (minutes, seconds) = base.split_once(':').unwrap_or((base, "0"));
+        let base =
+            Duration::from_secs(minutes.parse::<u64>().ok()? * 60 + seconds.parse::<u64>().ok()?);
+
+        *self = Self {
+            moves_per_session: moves_per_session.parse().ok()?,
+            base,
+            increment: Duration::try_from_secs_f64(increment.parse().ok()?).ok()?,
+            move_time: None,
+            clock: base,
+            ..*self
+        };
+
+        Some(())
+    }
+
+    /// Limits of the search of the move number `fullmove_number`
+    fn limits(&self, fullmove_number: usize) -> SearchLimits {
+        let moves_to_go = match self.moves_per_session {
+            0 => None,
+            moves => Some(moves - (fullmove_number.saturating_sub(1) as u32 % moves)),
+        };
+
+        SearchLimits {
+            depth: self.depth,
+            nodes: None,
+            time: Some(
+                self.move_time
+                    .unwrap_or_else(|| time_for_move(self.clock, self.increment, moves_to_go)),
+            ),
+        }
+    }
+}
+
+/// Engine driven through CECP, reading commands line by line and answering on `output`
+pub struct Xboard<W> {
+    output: SharedOutput<W>,
+    game_state: Arc<Mutex<GameState>>,
+    search: SearchThread,
+    /// Set when the move being searched must not be played, e.g., after `force` or `undo`
+    discard: Arc<AtomicBool>,
+    /// Whether the engine only checks the moves it is given, playing neither side
+    force: bool,
+    engine_color: Color,
+    time_control: TimeControl,
+    /// Whether to print the thinking output after each iteration
+    post: bool,
+}
+
+impl<W: Write + Send + 'static> Xboard<W> {
+    pub fn new(output: W) -> Self {
+        Self {
+            output: Arc::new(Mutex::new(output)),
+            game_state: Arc::new(Mutex::new(GameState::starting_position())),
+            search: SearchThread::new(Engine::new()),
+            discard: Arc::new(AtomicBool::new(false)),
+            force: false,
+            engine_color: Color::Black,
+            time_control: TimeControl::default(),
+            post: false,
+        }
+    }
+
+    /// Handle commands until `quit` or the end of the input, letting the last search finish
+    pub fn run(&mut self, input: impl BufRead) {
+        for line in input.lines() {
+            let Ok(line) = line
+            else {
+                break;
+            };
+            if !self.handle_command(&line) {
+                return;
+            }
+        }
+
+        self.search.finish();
+    }
+
+    /// Handle a single command, and return whether more commands should be read
+    pub fn handle_command(&mut self, command: &str) -> bool {
+        let words: Vec<&str> = command.split_whitespace().collect();
+
+        match words.as_slice() {
+            ["protover", ..] => {
+                self.send(&format!("feature {}", FEATURES.join(" ")));
+            }
+            ["new"] => self.new_game(),
+            ["force"] => {
+                self.cancel_search();
+                self.force = true;
+            }
+            ["go"] => {
+                self.cancel_search();
+                self.force = false;
+                self.engine_color = self.side_to_move();
+                self.think();
+            }
+            ["?"] => self.search.stop(),
+            ["usermove", text] => self.user_move(text),
+            ["setboard", fen @ ..] => self.set_board(&fen.join(" ")),
+            ["undo"] => self.take_back(1),
+            ["remove"] => self.take_back(2),
+            ["level", level @ ..] => {
+                if self.time_control.set_level(level).is_none() {
+                    self.send(&format!("Error (invalid time control): {command}"));
+                }
+            }
+            ["st", seconds] => self.set_number(command, seconds, |time_control, seconds| {
+                time_control.move_time = Some(Duration::from_secs(seconds));
+            }),
+            ["sd", depth] => self.set_number(command, depth, |time_control, depth| {
+                time_control.depth = Some(depth as usize);
+            }),
+            ["time", centiseconds] => {
+                self.set_number(command, centiseconds, |time_control, centiseconds| {
+                    time_control.clock = Duration::from_millis(centiseconds * 10);
+                })
+            }
+            ["result", ..] => {
+                self.cancel_search();
+                self.force = true;
+            }
+            ["ping", number] => self.send(&format!("pong {number}")),
+            ["post"] => self.post = true,
+            ["nopost"] => self.post = false,
+            ["quit"] => {
+                self.cancel_search();
+                return false;
+            }
+            // Commands needing no answer, or features the engine did not ask for
+            [
+                "xboard" | "accepted" | "rejected" | "otim" | "hard" | "easy" | "random"
+                | "computer" | "name" | "draw",
+                ..,
+            ]
+            | [] => {}
+            // Moves are sent without `usermove` by interfaces ignoring the feature
+            [text] if is_coordinate_move(text) => self.user_move(text),
+            _ => self.send(&format!("Error (unknown command): {command}")),
+        }
+
+        true
+    }
+
+    fn send(&self, line: &str) {
+        send(&self.output, line);
+    }
+
+    fn side_to_move(&self) -> Color {
+        match lock(&self.game_state).is_white_turn() {
+            true => Color::White,
+            false => Color::Black,
+        }
+    }
+
+    fn set_number(&mut self, command: &str, value: &str, set: impl FnOnce(&mut TimeControl, u64)) {
+        match value.parse() {
+            Ok(value) => set(&mut self.time_control, value),
+            Err(_) => self.send(&format!("Error (invalid number): {command}")),
+        }
+    }
+
+    /// Stop searching without playing the move found
+    fn cancel_search(&mut self) {
+        self.discard.store(true, Ordering::Relaxed);
+        self.search.stop();
+    }
+
+    fn new_game(&mut self) {
+        self.cancel_search();
+        self.search.engine().new_game();
+        *lock(&self.game_state) = GameState::starting_position();
+        self.force = false;
+        self.engine_color = Color::Black;
+        self.time_control = TimeControl {
+            depth: None,
+            clock: self.time_control.base,
+            ..self.time_control
+        };
+    }
+
+    fn set_board(&mut self, fen: &str) {
+        self.cancel_search();
+
+        match GameState::from_fen(fen) {
+            Ok(game_state) => *lock(&self.game_state) = game_state,
+            Err(err) => self.send(&format!("tellusererror Illegal position: {err}")),
+        }
+    }
+
+    fn take_back(&mut self, moves: usize) {
+        self.cancel_search();
+
+        let mut game_state = lock(&self.game_state);
+        for _ in 0..moves {
+            game_state.undo();
+        }
+    }
+
+    /// Play the opponent's move, then answer it unless in force mode
+    fn user_move(&mut self, text: &str) {
+        self.cancel_search();
+
+        {
+            let mut game_state = lock(&self.game_state);
+            let played = parse_coordinate_move(&game_state, text)
+                .and_then(|next_move| game_state.play_move(next_move));
+            if let Err(err) = played {
+                self.send(&format!("Illegal move ({err}): {text}"));
+                return;
+            }
+            if report_outcome(&self.output, &game_state) {
+                return;
+            }
+        }
+
+        if !self.force && self.side_to_move() == self.engine_color {
+            self.think();
+        }
+    }
+
+    /// Search for the engine's move on another thread, which plays it once found
+    fn think(&mut self) {
+        let game_state = lock(&self.game_state).clone();
+        if game_state.is_over() {
+            return;
+        }
+
+        let limits = self.time_control.limits(game_state.fullmove_number());
+        let start = Instant::now();
+        let post = self.post;
+        let progress_output = Arc::clone(&self.output);
+        let output = Arc::clone(&self.output);
+        let shared_game_state = Arc::clone(&self.game_state);
+        let discard = Arc::clone(&self.discard);
+        discard.store(false, Ordering::Relaxed);
+
+        self.search.start(
+            game_state,
+            limits,
+            false,
+            move |result| {
+                if post {
+                    send(&progress_output, &thinking(result, start.elapsed()));
+                }
+            },
+            move |result| {
+                let Some(best_move) = result.best_move
+                else {
+                    return;
+                };
+                if discard.load(Ordering::Relaxed) {
+                    return;
+                }
+
+                let mut game_state = lock(&shared_game_state);
+                game_state.play_move(best_move).expect(INTERNAL_ERROR_09);
+                send(&output, &format!("move {best_move}"));
+
+                // Claim draws by repetition or by the fifty-move rule as soon as possible
+                if game_state.outcome().is_none() {
+                    game_state.claim_draw();
+                }
+                report_outcome(&output, &game_state);
+            },
+        );
+    }
+}
+
+/// Print the result of the game if it is over, and return whether it is
+fn report_outcome<W: Write>(output: &Mutex<W>, game_state: &GameState) -> bool {
+    match game_state.outcome() {
+        Some(outcome) => {
+            send(output, &format!("{} {{{outcome}}}", outcome.result()));
+            true
+        }
+        None => false,
+    }
+}
+
+/// Thinking output after each iteration: depth, score, time in centiseconds, nodes and
+/// principal variation
+fn thinking(result: &SearchResult, elapsed: Duration) -> String {
+    let principal_variation: Vec<String> = result
+        .principal_variation
+        .iter()
+        .map(|next_move| next_move.to_string())
+        .collect();
+    // CECP reports a mate in N moves as 100000 + N, and being mated in N moves as -100000 - N
+    let score = match result.mate_in() {
+        Some(moves) if moves > 0 => 100_000 + moves,
+        Some(moves) => -100_000 + moves,
+        None => result.score,
+    };
+
+    format!(
+        "{} {} {} {} {}",
+        result.depth,
+        score,
+        elapsed.as_millis() / 10,
+        result.nodes,
+        principal_variation.join(" ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_helper::SharedBuffer;
+
+    fn run(commands: &str) -> (Xboard<SharedBuffer>, Vec<String>) {
+        let buffer = SharedBuffer::default();
+        let mut xboard = Xboard::new(buffer.clone());
+
+        xboard.run(commands.as_bytes());
+
+        (xboard, buffer.lines())
+    }
+
+    fn fen(xboard: &Xboard<SharedBuffer>) -> String {
+        lock(&xboard.game_state).to_fen()
+    }
+
+    #[test]
+    fn test_feature_negotiation() {
+        let (_, lines) = run("xboard\nprotover 2\naccepted usermove\nping 7\n");
+
+        assert!(lines[0].starts_with("feature myname=\"Rusty Chess "));
+        assert!(lines[0].contains(" usermove=1 ") && lines[0].ends_with(" done=1"));
+        assert_eq!(lines[1], "pong 7");
+    }
+
+    #[test]
+    fn test_engine_answers_as_black() {
+        let (xboard, lines) = run("new\nsd 2\nusermove e2e4\n");
+
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].starts_with("move "));
+        assert!(xboard.side_to_move() == Color::White);
+        assert_eq!(lock(&xboard.game_state).history().len(), 2);
+    }
+
+    #[test]
+    fn test_force_mode_and_go() {
+        let (xboard, lines) = run("new\nforce\nusermove e2e4\ne7e5\n");
+
+        assert!(lines.is_empty());
+        assert_eq!(
+            fen(&xboard),
+            "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2"
+        );
+
+        let (xboard, lines) = run("new\nforce\nusermove e2e4\nsd 1\ngo\n");
+        assert_eq!(lines.len(), 1);
+        assert!(xboard.engine_color == Color::Black);
+        assert!(xboard.side_to_move() == Color::White);
+    }
+
+    #[test]
+    fn test_illegal_and_unknown_commands() {
+        let (xboard, lines) = run("new\nforce\nusermove e2e5\nfoo\ne0e9\n");
+
+        assert!(lines[0].starts_with("Illegal move (") && lines[0].ends_with("): e2e5"));
+        assert_eq!(lines[1], "Error (unknown command): foo");
+        assert_eq!(lines[2], "Error (unknown command): e0e9");
+        assert!(lock(&xboard.game_state).history().is_empty());
+    }
+
+    #[test]
+    fn test_setboard_and_result() {
+        let (_, lines) = run("new\nsetboard 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1\nsd 3\ngo\n");
+
+        assert_eq!(lines, ["move a1a8", "1-0 {Checkmate! White wins}"]);
+
+        let (_, lines) = run("setboard 8/8/8 w - - 0 1\n");
+        assert!(lines[0].starts_with("tellusererror Illegal position"));
+    }
+
+    #[test]
+    fn test_undo_and_remove() {
+        let (xboard, _) = run("new\nforce\ne2e4\ne7e5\ng1f3\nundo\n");
+        assert_eq!(lock(&xboard.game_state).history().len(), 2);
+
+        let (xboard, _) = run("new\nforce\ne2e4\ne7e5\ng1f3\nremove\n");
+        assert_eq!(lock(&xboard.game_state).history().len(), 1);
+    }
+
+    #[test]
+    fn test_thinking_output() {
+        let (_, lines) = run("new\npost\nsd 2\ne2e4\n");
+
+        assert!(lines[0].starts_with("1 "));
+        assert!(lines[1].starts_with("2 "));
+        assert!(lines[2].starts_with("move "));
+    }
+
+    #[test]
+    fn test_thinking_output_mate_score() {
+        let (_, lines) =
+            run("new\nforce\nsetboard 6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1\npost\nsd 3\ngo\n");
+
+        assert_eq!(lines[0].split(' ').nth(1), Some("100001"));
+        assert!(lines.contains(&"move a1a8".to_string()));
+    }
+
+    #[test]
+    fn test_time_controls() {
+        let mut time_control = TimeControl::default();
+
+        assert!(time_control.set_level(&["40", "0:30", "0"]).is_some());
+        assert_eq!(time_control.base, Duration::from_secs(30));
+        assert!(time_control.set_level(&["0", "2", "1.5"]).is_some());
+        assert_eq!(time_control.base, Duration::from_secs(120));
+        assert_eq!(time_control.increment, Duration::from_millis(1500));
+        assert!(time_control.set_level(&["0", "x", "1"]).is_none());
+
+        // 40 moves in 5 minutes: the 40th move of the session gets the whole clock but the
+        // overhead, as the clock is refilled after it
+        let mut time_control = TimeControl {
+            clock: Duration::from_secs(10),
+            ..Default::default()
+        };
+        assert_eq!(
+            time_control.limits(40).time,
+            Some(Duration::from_millis(9950))
+        );
+        assert_eq!(
+            time_control.limits(41).time,
+            Some(Duration::from_millis(250))
+        );
+
+        time_control.move_time = Some(Duration::from_secs(2));
+        time_control.depth = Some(4);
+        assert_eq!(
+            time_control.limits(1),
+            SearchLimits {
+                depth: Some(4),
+                nodes: None,
+                time: Some(Duration::from_secs(2)),
+            }
+        );
+    }
+}
@@ -1,3 +1,2 @@
-pub mod helper_functions;
 #[cfg(test)]
 pub mod test_helper;
@@ -1,47 +1,117 @@
-use crate::io::file_manager::initial_positions;
-use crate::movement::ChessPosition;
-use crate::pieces::{Color, Piece, PieceType};
-use crate::utils::helper_functions::get_next_char;
+use crate::io::file_manager::open_file;
+use crate::io::positions::{DEFAULT_POSITIONS, read_positions};
+use crate::movement::Move;
+use crate::pieces::Color;
 use crate::{Board, GameState};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 
-pub fn setup_game_state(positions_file: Option<&str>) -> GameState {
-    let mut game_state = GameState::new();
-    game_state.initialize(positions_file);
-    game_state
+/// Path of a file in the positions fixtures directory, independent of the working directory
+pub fn fixture_path(positions_file: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("res/positions")
+        .join(positions_file)
 }
 
-pub fn setup_board(positions_file: Option<&str>) -> Board {
-    let mut board = Board::new();
-    for wrapped_line in initial_positions(positions_file) {
-        let line = wrapped_line.expect("Error reading file line");
-        let mut chars = line.chars();
+/// Deterministic xorshift generator, so that random games can be replayed
+pub struct Xorshift(pub u64);
+
+impl Xorshift {
+    pub fn next(&mut self, bound: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % bound as u64) as usize
+    }
+}
+
+/// Position rich in castling, en passant, pins and promotions, known as "Kiwipete"
+pub const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
 
-        let piece_color: Color = get_next_char(&line, &mut chars)
-            .try_into()
-            .unwrap_or_else(|_| panic!("Could not parse color character from line {line}"));
+/// Starting points of [`random_playouts`]: the initial position, Kiwipete, an endgame with
+/// en passant pins and a race of promotions
+pub const PLAYOUT_POSITIONS: [&str; 4] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    KIWIPETE,
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1",
+];
 
-        let piece_type: PieceType = get_next_char(&line, &mut chars)
-            .try_into()
-            .unwrap_or_else(|_| panic!("Could not parse piece character from line {line}"));
+/// Play `games` random games of at most `plies` moves from each of the [`PLAYOUT_POSITIONS`],
+/// calling `visit` with each position and the move about to be played in it. The final position
+/// of every game is returned, in order, with its history.
+pub fn random_playouts(
+    seed: u64,
+    games: usize,
+    plies: usize,
+    mut visit: impl FnMut(&GameState, Move),
+) -> Vec<GameState> {
+    let mut rng = Xorshift(seed);
+    let mut final_states = Vec::new();
 
-        let chess_col = get_next_char(&line, &mut chars);
+    for fen in PLAYOUT_POSITIONS {
+        for _ in 0..games {
+            let mut game_state = GameState::from_fen(fen).unwrap();
 
-        let chess_line = get_next_char(&line, &mut chars);
+            for _ in 0..plies {
+                let legal_moves = game_state.legal_moves();
+                if legal_moves.is_empty() || game_state.is_over() {
+                    break;
+                }
 
-        let piece_position = ChessPosition::new(chess_line, chess_col)
-            .try_into()
-            .unwrap_or_else(|_| {
-                panic!("Could not convert ChessPosition {chess_col}{chess_line} to Position")
-            });
+                let next_move = legal_moves[rng.next(legal_moves.len())];
+                visit(&game_state, next_move);
+                game_state.play_move(next_move).unwrap();
+            }
 
-        if board.is_position_occupied(piece_position) {
-            panic!(
-                "Duplicate piece position in initial setup: {chess_col}{chess_line} (line: {line})"
-            );
+            final_states.push(game_state);
         }
+    }
+
+    final_states
+}
+
+pub fn setup_game_state(positions_file: Option<&str>) -> GameState {
+    setup_game_state_with_turn(positions_file, Color::White)
+}
 
-        board.add_piece(Piece::new(piece_type, piece_color), piece_position);
+pub fn setup_game_state_with_turn(positions_file: Option<&str>, turn: Color) -> GameState {
+    GameState::from_board(setup_board(positions_file), turn)
+        .unwrap_or_else(|err| panic!("Invalid test position: {err}"))
+}
+
+pub fn setup_board(positions_file: Option<&str>) -> Board {
+    let board = match positions_file {
+        Some(positions_file) => open_file(&fixture_path(positions_file))
+            .map_err(Into::into)
+            .and_then(read_positions),
+        None => read_positions(DEFAULT_POSITIONS.as_bytes()),
+    };
+
+    board.unwrap_or_else(|err| panic!("Invalid test board: {err}"))
+}
+
+#[derive(Clone, Default)]
+/// Output written to by one side while the test keeps a handle on it to read it
+pub struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuffer {
+    pub fn lines(&self) -> Vec<String> {
+        String::from_utf8(self.0.lock().unwrap().clone())
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect()
     }
+}
 
-    board
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
 }
@@ -1,7 +1,13 @@
-use chess::GameState;
-use chess::errors::{ChessPositionError, MoveError, PgnError};
-use chess::movement::Position;
+use chess::errors::{
+    ChessPositionError, MoveError, PgnError, PgnReadError, SetupError, SetupErrorKind,
+};
+use chess::movement::{AnyNotation, Coordinate, LongAlgebraic, Move, Position, San};
+use chess::pgn::{GameResult, PgnTags, games_from_file, games_from_str};
+use chess::pieces::{Color, PieceType};
+use chess::{GameOutcome, GameState};
+use std::io::BufReader;
 use std::mem::discriminant;
+use std::path::PathBuf;
 
 macro_rules! setup_board {
         ( $game_state:expr, $( $x:expr ),* ) => {
@@ -16,13 +22,29 @@ macro_rules! setup_board {
 
 pub fn setup() -> GameState {
     let mut game_state = GameState::new();
-    game_state.initialize(None);
     game_state
+        .initialize(None)
+        .expect("The default positions should be valid");
+    game_state
+}
+
+pub fn fixture_path(file: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("res/positions")
+        .join(file)
+}
+
+pub fn pgn_path(file: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("res/pgn")
+        .join(file)
 }
 
 pub fn setup_with_positions(file: &str) -> GameState {
     let mut game_state = GameState::new();
-    game_state.initialize(Some(file));
+    game_state
+        .initialize(Some(&fixture_path(file)))
+        .unwrap_or_else(|err| panic!("Invalid positions in {file}: {err}"));
     game_state
 }
 
@@ -401,6 +423,140 @@ fn test_uninitialized_game_state() {
     let _ = game_state.handle_move("Nd5");
 }
 
+#[test]
+fn test_positions_from_str_and_reader() {
+    let positions = "WKe1\nWPe2\nBKe8\nBPe7\n";
+
+    let mut from_str = GameState::from_positions_str(positions).unwrap();
+    let mut from_reader =
+        GameState::from_positions_reader(BufReader::new(positions.as_bytes())).unwrap();
+
+    assert_eq!(from_str, from_reader);
+
+    setup_board!(from_str, "e4", "e5");
+    setup_board!(from_reader, "e4", "e5");
+
+    assert_eq!(from_str, from_reader);
+}
+
+#[test]
+fn test_positions_file_outside_positions_directory() {
+    let path = std::env::temp_dir().join(format!("chess_positions_{}.txt", std::process::id()));
+    std::fs::write(&path, "WKe1\nBKe8\nWQd1\n").unwrap();
+
+    let game_state = GameState::from_positions_file(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(game_state.unwrap().board().into_iter().count(), 3);
+}
+
+#[test]
+fn test_invalid_setup_is_reported_without_initializing() {
+    let mut game_state = GameState::new();
+
+    let result = game_state.initialize(Some(&fixture_path("tests/setup/invalid_piece.txt")));
+
+    assert_eq!(
+        result,
+        Err(SetupError::InvalidLine(
+            3,
+            SetupErrorKind::InvalidPiece('Z')
+        ))
+    );
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Line 3: Invalid piece character: Z"
+    );
+    assert_eq!(game_state, GameState::new());
+}
+
+#[test]
+fn test_pgn_file_games_are_replayed() -> Result<(), PgnReadError> {
+    let games = games_from_file(pgn_path("club_games.pgn"))?;
+
+    assert_eq!(games.len(), 4);
+    assert_eq!(games[0].tag("White"), Some("Paul Morphy"));
+    assert_eq!(games[0].moves().len(), 33);
+    assert_eq!(
+        games.iter().map(|game| game.result()).collect::<Vec<_>>(),
+        [
+            GameResult::WhiteWins,
+            GameResult::BlackWins,
+            GameResult::Draw,
+            GameResult::Unknown,
+        ]
+    );
+
+    let final_states = games
+        .iter()
+        .map(|game| game.replay())
+        .collect::<Result<Vec<_>, _>>()?;
+
+    assert!(final_states[0].verify_checkmate());
+    assert!(final_states[0].is_black_turn());
+    assert!(final_states[1].verify_checkmate());
+    assert!(final_states[1].is_white_turn());
+    assert!(!final_states[2].verify_checkmate());
+    assert!(final_states[3].is_black_turn());
+
+    Ok(())
+}
+
+#[test]
+fn test_pgn_file_reports_failing_game_and_ply() -> Result<(), PgnReadError> {
+    let games = games_from_file(pgn_path("illegal_move.pgn"))?;
+
+    assert!(games[0].replay().is_ok());
+
+    let error = games[1].replay().unwrap_err();
+    assert!(matches!(
+        error,
+        PgnReadError::IllegalMove { game: 2, ply: 5, ref san, .. } if san == "e5"
+    ));
+    assert!(error.to_string().starts_with("Game 2, ply 5 (e5): "));
+
+    Ok(())
+}
+
+#[test]
+fn test_move_notations() {
+    let game_state = setup_with_positions("tests/promotion.txt");
+    let mut promotion = Move::new(Position::new(1, 4), Position::new(0, 4));
+    promotion.promotion = Some(PieceType::Queen);
+
+    assert_eq!(promotion.to_string(), "e7e8q");
+    assert_eq!(game_state.to_san(promotion), "e8=Q+");
+
+    let mut capture_promotion = Move::new(Position::new(1, 4), Position::new(0, 3));
+    capture_promotion.promotion = Some(PieceType::Queen);
+
+    assert_eq!(capture_promotion.to_string(), "e7d8q");
+    assert_eq!(game_state.to_san(capture_promotion), "exd8=Q");
+}
+
+#[test]
+fn test_played_game_is_exported_to_pgn() -> Result<(), PgnReadError> {
+    let mut game_state = setup();
+    setup_board!(game_state, "e4", "e5", "Bc4", "Nc6", "Qh5", "Nf6", "Qxf7#");
+    let tags = PgnTags {
+        white: "Alice".to_owned(),
+        black: "Bob".to_owned(),
+        ..Default::default()
+    };
+
+    let pgn = game_state.to_pgn(&tags);
+
+    assert!(pgn.contains("[Result \"1-0\"]"));
+    assert_eq!(game_state.history().len(), 7);
+
+    let games = games_from_str(&pgn)?;
+    assert_eq!(games[0].tag("White"), Some("Alice"));
+    assert_eq!(games[0].result(), GameResult::WhiteWins);
+    assert_eq!(games[0].replay()?, game_state);
+
+    Ok(())
+}
+
 #[test]
 fn test_scholars_mate_full_game() {
     let mut game_state = setup();
@@ -410,6 +566,31 @@ fn test_scholars_mate_full_game() {
     assert!(game_state.verify_checkmate());
 }
 
+#[test]
+fn test_legal_moves() -> Result<(), MoveError> {
+    let mut game_state = setup();
+
+    assert_eq!(game_state.legal_moves().len(), 20);
+
+    setup_board!(game_state, "e4", "e5", "Bc4", "Nc6", "Qh5", "Nf6");
+
+    assert_eq!(game_state.legal_moves().len(), 43);
+
+    game_state.handle_move("Qxf7#")?;
+
+    assert!(game_state.legal_moves().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_stalemate_ignores_pinned_piece_moves() {
+    let game_state = setup_with_positions("tests/stalemate_pinned_piece.txt");
+
+    assert!(game_state.is_stalemate());
+    assert!(!game_state.verify_checkmate());
+}
+
 #[test]
 fn test_castling_rights_persistence_after_failed_move() -> Result<(), MoveError> {
     let mut game_state = setup();
@@ -531,36 +712,378 @@ fn test_castling_fails_after_rook_has_moved() {
         MoveError::InvalidCastle("This move is not allowed"),
     );
 }
-//
-// #[test]
-// fn test_trailing_alphabetic_characters_are_rejected() {
-//     let mut game_state = setup();
-//
-//     assert_failed_move_preserves_state(
-//         &mut game_state,
-//         "e4abc",
-//         PgnError::InvalidCharacter('a').into(),
-//     );
-// }
-//
-// #[test]
-// fn test_trailing_symbol_characters_are_rejected() {
-//     let mut game_state = setup();
-//
-//     assert_failed_move_preserves_state(
-//         &mut game_state,
-//         "e4?",
-//         PgnError::InvalidCharacter('?').into(),
-//     );
-// }
-//
-// #[test]
-// fn test_trailing_characters_after_long_castle_are_rejected() {
-//     let mut game_state = setup_with_positions("tests/validate_castling_path_success.txt");
-//
-//     assert_failed_move_preserves_state(
-//         &mut game_state,
-//         "O-O-Oxyz",
-//         PgnError::InvalidCharacter('x').into(),
-//     );
-// }
+
+#[test]
+fn test_en_passant_capture() -> Result<(), MoveError> {
+    let mut game_state = setup();
+    setup_board!(game_state, "Nf3", "d5", "e3", "d4", "c4");
+
+    make_and_validate_move(
+        &mut game_state,
+        "dxc3",
+        Position::new(4, 3),
+        Position::new(5, 2),
+    )?;
+
+    assert!(game_state.get_piece(Position::new(4, 2)).is_none());
+    assert_eq!(game_state.captured_white_pieces().len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_en_passant_not_allowed_after_single_step() {
+    let mut game_state = setup();
+    setup_board!(game_state, "e4", "d6", "e5", "d5");
+
+    assert_failed_move_preserves_state(
+        &mut game_state,
+        "exd6",
+        MoveError::InvalidCapture("Destination square is empty"),
+    );
+}
+
+#[test]
+fn test_promotion() -> Result<(), MoveError> {
+    let mut game_state = setup_with_positions("tests/promotion.txt");
+
+    game_state.handle_move("e8=Q")?;
+
+    let promoted = game_state.get_piece(Position::new(0, 4)).unwrap();
+    assert!(matches!(promoted.piece_type, PieceType::Queen));
+    assert_eq!(promoted.color, Color::White);
+    assert!(game_state.get_piece(Position::new(1, 4)).is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_capture_promotion() -> Result<(), MoveError> {
+    let mut game_state = setup_with_positions("tests/promotion.txt");
+
+    game_state.handle_move("exd8=N")?;
+
+    let promoted = game_state.get_piece(Position::new(0, 3)).unwrap();
+    assert!(matches!(promoted.piece_type, PieceType::Knight));
+    assert_eq!(game_state.captured_black_pieces().len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_missing_promotion_error() {
+    let mut game_state = setup_with_positions("tests/promotion.txt");
+
+    assert_failed_move_preserves_state(&mut game_state, "e8", MoveError::MissingPromotion);
+    assert_failed_move_preserves_state(&mut game_state, "exd8", MoveError::MissingPromotion);
+}
+
+#[test]
+fn test_invalid_promotion_error() {
+    let mut game_state = setup_with_positions("tests/promotion.txt");
+
+    assert_failed_move_preserves_state(
+        &mut game_state,
+        "e8=K",
+        MoveError::InvalidPromotion(
+            "A pawn can only be promoted to a Queen, Rook, Bishop or Knight",
+        ),
+    );
+    assert_failed_move_preserves_state(
+        &mut game_state,
+        "Ke2=Q",
+        MoveError::InvalidPromotion("Only a pawn reaching the last rank can be promoted"),
+    );
+    assert_failed_move_preserves_state(
+        &mut game_state,
+        "e8=",
+        PgnError::MissingCharacter("promotion").into(),
+    );
+}
+
+#[test]
+fn test_trailing_alphabetic_characters_are_rejected() {
+    let mut game_state = setup();
+
+    assert_failed_move_preserves_state(
+        &mut game_state,
+        "e4abc",
+        PgnError::InvalidCharacter('a').into(),
+    );
+}
+
+#[test]
+fn test_trailing_symbol_characters_are_rejected() {
+    let mut game_state = setup();
+
+    assert_failed_move_preserves_state(
+        &mut game_state,
+        "e4*",
+        PgnError::InvalidCharacter('*').into(),
+    );
+    assert_failed_move_preserves_state(
+        &mut game_state,
+        "e4!!!",
+        PgnError::InvalidCharacter('!').into(),
+    );
+}
+
+#[test]
+fn test_trailing_characters_after_long_castle_are_rejected() {
+    let mut game_state = setup_with_positions("tests/validate_castling_path_success.txt");
+
+    assert_failed_move_preserves_state(
+        &mut game_state,
+        "O-O-Oxyz",
+        PgnError::InvalidCharacter('x').into(),
+    );
+}
+
+#[test]
+fn test_trailing_characters_after_short_castle_are_rejected() {
+    let mut game_state = setup_with_positions("tests/validate_castling_path_success.txt");
+
+    assert_failed_move_preserves_state(
+        &mut game_state,
+        "O-O?x",
+        PgnError::InvalidCharacter('x').into(),
+    );
+}
+
+#[test]
+fn test_annotation_glyphs_are_accepted() -> Result<(), MoveError> {
+    let mut game_state = setup();
+
+    setup_board!(game_state, "e4!", "e5?", "Nf3!!", "Nc6??", "Bb5!?", "a6?!");
+
+    Ok(())
+}
+
+#[test]
+fn test_check_suffix() -> Result<(), MoveError> {
+    let mut game_state = setup();
+    setup_board!(game_state, "e4", "f6");
+
+    assert_failed_move_preserves_state(
+        &mut game_state,
+        "Nf3+",
+        MoveError::InvalidCheck("This move does not give check"),
+    );
+
+    game_state.handle_move("Qh5+")?;
+
+    Ok(())
+}
+
+#[test]
+fn test_checkmate_suffix() -> Result<(), MoveError> {
+    let mut game_state = setup();
+    setup_board!(game_state, "e4", "e5", "Bc4", "Nc6", "Qh5", "Nf6");
+
+    assert_failed_move_preserves_state(
+        &mut game_state,
+        "Bxf7#",
+        MoveError::InvalidCheck("This move does not checkmate"),
+    );
+
+    game_state.handle_move("Qxf7#!")?;
+
+    assert!(game_state.verify_checkmate());
+
+    Ok(())
+}
+
+#[test]
+fn test_castling_with_check_suffix() -> Result<(), MoveError> {
+    let mut game_state = setup_with_positions("tests/validate_castling_path_success.txt");
+
+    assert_failed_move_preserves_state(
+        &mut game_state,
+        "O-O-O+",
+        MoveError::InvalidCheck("This move does not give check"),
+    );
+
+    game_state.handle_move("O-O!")?;
+
+    Ok(())
+}
+
+#[test]
+fn test_takeback_and_replay_game() -> Result<(), MoveError> {
+    let mut game_state = setup();
+    setup_board!(game_state, "e4", "d5", "exd5", "Qxd5", "Nc3", "Qa5");
+    let played_state = game_state.clone();
+
+    for _ in 0..6 {
+        assert!(game_state.undo().is_some());
+    }
+
+    assert_eq!(game_state.to_fen(), setup().to_fen());
+    assert!(game_state.history().is_empty());
+    assert!(game_state.captured_white_pieces().is_empty());
+    assert!(game_state.captured_black_pieces().is_empty());
+
+    while game_state.redo().is_some() {}
+
+    assert_eq!(game_state, played_state);
+
+    game_state.undo();
+    game_state.handle_move("Qd8")?;
+
+    assert_eq!(game_state.redo(), None);
+    assert_eq!(game_state.history().last().unwrap().san, "Qd8");
+
+    Ok(())
+}
+
+#[test]
+fn test_fifty_move_rule_after_king_shuffle() -> Result<(), MoveError> {
+    let mut game_state = GameState::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+
+    // The Rook tours the board so that no position occurs five times, which would end the game
+    let rook_tour = [
+        "a2", "a3", "a4", "a5", "a6", "a7", "b7", "b6", "b5", "b4", "b3", "b2", "b1", "a1",
+    ];
+    for (index, rook_square) in rook_tour.iter().cycle().take(50).enumerate() {
+        assert!(!game_state.can_claim_fifty_move_draw());
+
+        let king_move = if index % 2 == 0 { "Kf8" } else { "Ke8" };
+        setup_board!(game_state, &format!("R{rook_square}"), king_move);
+    }
+
+    assert!(game_state.can_claim_fifty_move_draw());
+    assert!(game_state.claim_draw());
+    assert_eq!(game_state.result(), GameResult::Draw);
+
+    Ok(())
+}
+
+#[test]
+fn test_threefold_repetition_claim() -> Result<(), MoveError> {
+    let mut game_state = setup();
+    setup_board!(game_state, "e4", "e5", "Nf3", "Nc6");
+
+    for _ in 0..2 {
+        assert!(!game_state.can_claim_threefold_repetition());
+
+        setup_board!(game_state, "Ng1", "Nb8", "Nf3", "Nc6");
+    }
+
+    assert_eq!(game_state.repetition_count(), 3);
+    assert!(game_state.claim_draw());
+    assert_eq!(game_state.result(), GameResult::Draw);
+
+    Ok(())
+}
+
+#[test]
+fn test_fivefold_repetition_ends_game() -> Result<(), MoveError> {
+    // Perpetual check: the Queen shuttles between e8 and h5 while the King goes back and forth
+    let mut game_state = GameState::from_fen("6k1/6p1/8/7Q/8/8/8/6K1 w - - 0 1").unwrap();
+
+    for _ in 0..4 {
+        assert!(!game_state.is_fivefold_repetition());
+
+        setup_board!(game_state, "Qe8+", "Kh7", "Qh5+", "Kg8");
+    }
+
+    assert_eq!(game_state.repetition_count(), 5);
+    assert!(game_state.is_fivefold_repetition());
+    assert_eq!(game_state.result(), GameResult::Draw);
+
+    Ok(())
+}
+
+#[test]
+fn test_repetition_with_different_side_to_move() -> Result<(), MoveError> {
+    let mut game_state = GameState::from_fen("r3k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+
+    // White triangulates, so the starting placement comes back with Black to move
+    setup_board!(game_state, "Kd1", "Kd8", "Kd2", "Ke8", "Ke1");
+
+    assert_eq!(game_state.repetition_count(), 1);
+
+    setup_board!(game_state, "Kd8", "Kd1", "Ke8");
+
+    assert_eq!(game_state.repetition_count(), 1);
+
+    setup_board!(game_state, "Ke1");
+
+    assert_eq!(game_state.repetition_count(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_insufficient_material_after_last_capture() -> Result<(), MoveError> {
+    let mut game_state = GameState::from_fen("4k3/8/8/8/8/8/3r4/4K3 w - - 0 1").unwrap();
+
+    assert!(!game_state.is_insufficient_material());
+
+    game_state.handle_move("Kxd2")?;
+
+    assert!(game_state.is_insufficient_material());
+    assert_eq!(game_state.captured_black_pieces().len(), 1);
+    assert_eq!(game_state.result(), GameResult::Draw);
+
+    Ok(())
+}
+
+#[test]
+fn test_game_outcomes() -> Result<(), MoveError> {
+    let mut game_state = setup();
+    setup_board!(game_state, "e4", "e5", "Bc4", "Nc6", "Qh5", "Nf6");
+
+    assert_eq!(game_state.outcome(), None);
+
+    game_state.handle_move("Qxf7#")?;
+
+    assert_eq!(
+        game_state.outcome(),
+        Some(GameOutcome::Checkmate(Color::White))
+    );
+
+    game_state.undo();
+    game_state.resign();
+
+    assert_eq!(
+        game_state.outcome(),
+        Some(GameOutcome::Resignation(Color::Black))
+    );
+    assert!(game_state.is_over());
+
+    game_state.undo();
+
+    assert!(!game_state.is_over());
+
+    Ok(())
+}
+
+#[test]
+fn test_moves_in_other_notations() -> Result<(), MoveError> {
+    let mut game_state = setup();
+
+    game_state.handle_move_as("e2e4", &Coordinate)?;
+    game_state.handle_move_as("e7-e5", &LongAlgebraic)?;
+    game_state.handle_move_as("Nf3", &San)?;
+    game_state.handle_move_as("b8c6", &AnyNotation)?;
+    game_state.handle_move_as("Bf1-c4", &AnyNotation)?;
+
+    let history: Vec<&str> = game_state
+        .history()
+        .iter()
+        .map(|played| played.san.as_str())
+        .collect();
+    assert_eq!(history, ["e4", "e5", "Nf3", "Nc6", "Bc4"]);
+
+    assert_eq!(
+        game_state.handle_move_as("g8g6", &Coordinate),
+        Err(MoveError::InvalidMove("The piece cannot go to this square"))
+    );
+    assert_eq!(
+        game_state.handle_move_as("Bf8xc5", &LongAlgebraic),
+        Err(MoveError::InvalidCapture("Destination square is empty"))
+    );
+
+    Ok(())
+}